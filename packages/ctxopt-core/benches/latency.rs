@@ -35,34 +35,31 @@ fn bench_token_estimation(c: &mut Criterion) {
 }
 
 // ============================================================================
-// ANSI Stripping Benchmarks
+// Terminal Rendering Benchmarks
 // ============================================================================
+//
+// Remplace l'ancien `bench_strip_ansi` (regex sur les bytes bruts): le
+// rendu ANSI passe maintenant par l'écran virtuel `vt100` dans
+// `StreamAnalyzer::analyze`, donc on mesure le coût du pipeline complet
+// plutôt qu'une regex isolée.
 
-fn bench_strip_ansi(c: &mut Criterion) {
+fn bench_terminal_render(c: &mut Criterion) {
     let ansi_text = include_str!("fixtures/ansi_output.txt");
     let ansi_repeated = ansi_text.repeat(100);
 
-    let mut group = c.benchmark_group("strip_ansi");
+    let mut group = c.benchmark_group("terminal_render");
 
     group.bench_function("small", |b| {
         b.iter(|| {
-            black_box(
-                PATTERNS
-                    .ansi_escape
-                    .replace_all(black_box(ansi_text), "")
-                    .to_string(),
-            )
+            let mut analyzer = StreamAnalyzer::new();
+            black_box(analyzer.analyze(black_box(ansi_text.as_bytes())))
         });
     });
 
     group.bench_function("large", |b| {
         b.iter(|| {
-            black_box(
-                PATTERNS
-                    .ansi_escape
-                    .replace_all(black_box(&ansi_repeated), "")
-                    .to_string(),
-            )
+            let mut analyzer = StreamAnalyzer::new();
+            black_box(analyzer.analyze(black_box(ansi_repeated.as_bytes())))
         });
     });
 
@@ -178,7 +175,7 @@ fn bench_stream_analyzer(c: &mut Criterion) {
     group.bench_function("analyze_typescript", |b| {
         b.iter(|| {
             let mut analyzer = StreamAnalyzer::new();
-            black_box(analyzer.analyze(black_box(ts_errors)))
+            black_box(analyzer.analyze(black_box(ts_errors.as_bytes())))
         });
     });
 
@@ -186,7 +183,7 @@ fn bench_stream_analyzer(c: &mut Criterion) {
     group.bench_function("analyze_rust", |b| {
         b.iter(|| {
             let mut analyzer = StreamAnalyzer::new();
-            black_box(analyzer.analyze(black_box(rust_errors)))
+            black_box(analyzer.analyze(black_box(rust_errors.as_bytes())))
         });
     });
 
@@ -194,7 +191,7 @@ fn bench_stream_analyzer(c: &mut Criterion) {
     group.bench_function("analyze_mixed", |b| {
         b.iter(|| {
             let mut analyzer = StreamAnalyzer::new();
-            black_box(analyzer.analyze(black_box(mixed_output)))
+            black_box(analyzer.analyze(black_box(mixed_output.as_bytes())))
         });
     });
 
@@ -202,7 +199,7 @@ fn bench_stream_analyzer(c: &mut Criterion) {
     group.bench_function("analyze_large", |b| {
         b.iter(|| {
             let mut analyzer = StreamAnalyzer::new();
-            black_box(analyzer.analyze(black_box(large_output)))
+            black_box(analyzer.analyze(black_box(large_output.as_bytes())))
         });
     });
 
@@ -210,10 +207,24 @@ fn bench_stream_analyzer(c: &mut Criterion) {
     group.bench_function("analyze_ansi", |b| {
         b.iter(|| {
             let mut analyzer = StreamAnalyzer::new();
-            black_box(analyzer.analyze(black_box(ansi_output)))
+            black_box(analyzer.analyze(black_box(ansi_output.as_bytes())))
         });
     });
 
+    // Compute the robust SessionStats summary over an analyzer that has
+    // already accumulated a realistic mix of chunks, to validate that
+    // `winsorized_mean`/`std_dev` stay cheap even over a long-running session
+    group.bench_function("session_stats_summary", |b| {
+        let mut analyzer = StreamAnalyzer::new();
+        for _ in 0..200 {
+            analyzer.analyze(ts_errors.as_bytes());
+            analyzer.analyze(rust_errors.as_bytes());
+            analyzer.analyze(mixed_output.as_bytes());
+        }
+
+        b.iter(|| black_box(analyzer.session_stats().summary()));
+    });
+
     group.finish();
 }
 
@@ -231,8 +242,7 @@ fn bench_full_pipeline(c: &mut Criterion) {
 
             // Split into chunks and analyze each
             for chunk in mixed_output.as_bytes().chunks(1024) {
-                let text = std::str::from_utf8(chunk).unwrap_or("");
-                let _ = analyzer.analyze(black_box(text));
+                let _ = analyzer.analyze(black_box(chunk));
             }
 
             black_box(analyzer)
@@ -243,7 +253,7 @@ fn bench_full_pipeline(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_token_estimation,
-    bench_strip_ansi,
+    bench_terminal_render,
     bench_pattern_detection,
     bench_stream_analyzer,
     bench_full_pipeline,
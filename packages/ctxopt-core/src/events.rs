@@ -0,0 +1,40 @@
+//! Évènements de session sérialisables, exposés en JSON newline-delimited
+//!
+//! Modélisé sur le `JsonEmitter` de rustc: `CtxOptSession::read_json` restitue
+//! la même analyse/injection que `CtxOptSession::read`, mais comme un flux
+//! d'objets `SessionEvent` (un `Read` suivi d'un `Suggestion` par suggestion
+//! émise) plutôt que des chaînes `format_for_display()` destinées à l'humain,
+//! pour que des wrappers Node.js alimentent une pipeline de logging/télémétrie
+//! sans parser les messages ANSI.
+
+use serde::Serialize;
+
+/// Évènement de session, un objet JSON par ligne
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// Résultat d'un appel à `read()`
+    Read {
+        /// Estimation de tokens pour le chunk lu
+        token_estimate: usize,
+        /// Types de contenu détectés (format `Debug` du `ContentType`)
+        detected_types: Vec<String>,
+    },
+    /// Une suggestion générée pour ce chunk
+    Suggestion {
+        /// Type de suggestion (format `Debug` du `SuggestionType`)
+        #[serde(rename = "type")]
+        suggestion_type: String,
+        /// Estimation des tokens économisés par cette suggestion
+        tokens: usize,
+        /// Message brut de `Suggestion::display_message` (codes ANSI inclus)
+        message: String,
+    },
+}
+
+impl SessionEvent {
+    /// Sérialise l'évènement en une ligne JSON (format newline-delimited)
+    pub fn to_json_line(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
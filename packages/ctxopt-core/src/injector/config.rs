@@ -0,0 +1,176 @@
+//! Configuration lint-style des suggestions
+//!
+//! Modélisé sur le système de niveaux de lint de rustc (`allow`/`warn`/`deny`):
+//! chaque `SuggestionType` peut être indépendamment autorisé, laissé tel quel,
+//! ou escaladé, avec son propre seuil de déclenchement et son propre plafond
+//! par session. `ContextInjector::should_inject` consulte cette configuration
+//! au lieu des constantes et littéraux qui vivaient auparavant dans les
+//! branches du `match`.
+
+use super::templates::SuggestionType;
+
+/// Niveau lint-style appliqué à un type de suggestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// La suggestion est entièrement supprimée, quel que soit le contenu détecté
+    Allow,
+    /// Comportement normal: déclenche au-dessus du seuil configuré
+    Warn,
+    /// Escalade: bypasse le throttle et l'anti-répétition pour ne rien manquer
+    Deny,
+}
+
+impl Level {
+    /// Parse un niveau depuis son libellé (`"allow"`, `"warn"`, `"deny"`),
+    /// insensible à la casse. Utilisé par `CtxOptSession::set_suggestion_level`
+    /// pour accepter les niveaux passés depuis Node.js.
+    pub(crate) fn parse(level: &str) -> Option<Self> {
+        match level.to_ascii_lowercase().as_str() {
+            "allow" => Some(Self::Allow),
+            "warn" => Some(Self::Warn),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Configuration d'un type de suggestion: niveau, seuil de déclenchement,
+/// et plafond optionnel d'occurrences par session.
+#[derive(Debug, Clone, Copy)]
+pub struct SuggestionConfig {
+    /// Niveau lint-style
+    pub level: Level,
+    /// Seuil de déclenchement (nombre d'erreurs, taille en octets, etc.)
+    pub threshold: usize,
+    /// Plafond d'injections de ce type par session (`None` = illimité)
+    pub max_per_session: Option<usize>,
+}
+
+impl SuggestionConfig {
+    const fn new(level: Level, threshold: usize, max_per_session: Option<usize>) -> Self {
+        Self {
+            level,
+            threshold,
+            max_per_session,
+        }
+    }
+}
+
+/// Configuration complète de l'injecteur, une entrée par `SuggestionType`
+///
+/// Les valeurs par défaut reproduisent exactement le comportement historique
+/// de `ContextInjector` (3 erreurs de build, 10KB d'output, 3 rappels prompt).
+#[derive(Debug, Clone)]
+pub struct InjectorConfig {
+    pub build_errors: SuggestionConfig,
+    pub large_output: SuggestionConfig,
+    pub file_read: SuggestionConfig,
+    pub prompt_reminder: SuggestionConfig,
+}
+
+impl Default for InjectorConfig {
+    fn default() -> Self {
+        Self {
+            build_errors: SuggestionConfig::new(Level::Warn, 3, None),
+            large_output: SuggestionConfig::new(Level::Warn, 10_000, None),
+            file_read: SuggestionConfig::new(Level::Warn, 0, None),
+            prompt_reminder: SuggestionConfig::new(Level::Warn, 0, Some(3)),
+        }
+    }
+}
+
+impl InjectorConfig {
+    /// Retourne la configuration applicable à un `SuggestionType`
+    pub fn for_type(&self, suggestion_type: &SuggestionType) -> &SuggestionConfig {
+        match suggestion_type {
+            SuggestionType::BuildErrors => &self.build_errors,
+            SuggestionType::LargeOutput => &self.large_output,
+            SuggestionType::FileRead => &self.file_read,
+            SuggestionType::PromptReminder => &self.prompt_reminder,
+        }
+    }
+
+    /// Fixe le niveau d'un type de suggestion
+    pub fn set_level(&mut self, suggestion_type: &SuggestionType, level: Level) {
+        self.config_mut(suggestion_type).level = level;
+    }
+
+    /// Fixe le seuil de déclenchement d'un type de suggestion
+    pub fn set_threshold(&mut self, suggestion_type: &SuggestionType, threshold: usize) {
+        self.config_mut(suggestion_type).threshold = threshold;
+    }
+
+    /// Fixe le plafond par session d'un type de suggestion
+    pub fn set_max_per_session(&mut self, suggestion_type: &SuggestionType, max: Option<usize>) {
+        self.config_mut(suggestion_type).max_per_session = max;
+    }
+
+    fn config_mut(&mut self, suggestion_type: &SuggestionType) -> &mut SuggestionConfig {
+        match suggestion_type {
+            SuggestionType::BuildErrors => &mut self.build_errors,
+            SuggestionType::LargeOutput => &mut self.large_output,
+            SuggestionType::FileRead => &mut self.file_read,
+            SuggestionType::PromptReminder => &mut self.prompt_reminder,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_historical_thresholds() {
+        let config = InjectorConfig::default();
+        assert_eq!(config.build_errors.threshold, 3);
+        assert_eq!(config.large_output.threshold, 10_000);
+        assert_eq!(config.prompt_reminder.max_per_session, Some(3));
+    }
+
+    #[test]
+    fn test_set_level_allow_precedence() {
+        let mut config = InjectorConfig::default();
+        config.set_level(&SuggestionType::LargeOutput, Level::Allow);
+        assert_eq!(config.for_type(&SuggestionType::LargeOutput).level, Level::Allow);
+        // Les autres types ne sont pas affectés
+        assert_eq!(config.for_type(&SuggestionType::BuildErrors).level, Level::Warn);
+    }
+
+    #[test]
+    fn test_set_level_deny_precedence() {
+        let mut config = InjectorConfig::default();
+        config.set_level(&SuggestionType::BuildErrors, Level::Deny);
+        assert_eq!(config.for_type(&SuggestionType::BuildErrors).level, Level::Deny);
+    }
+
+    #[test]
+    fn test_set_threshold_per_type() {
+        let mut config = InjectorConfig::default();
+        config.set_threshold(&SuggestionType::BuildErrors, 10);
+        assert_eq!(config.for_type(&SuggestionType::BuildErrors).threshold, 10);
+        // Les autres seuils restent par défaut
+        assert_eq!(config.for_type(&SuggestionType::LargeOutput).threshold, 10_000);
+    }
+
+    #[test]
+    fn test_set_max_per_session_per_type() {
+        let mut config = InjectorConfig::default();
+        config.set_max_per_session(&SuggestionType::LargeOutput, Some(5));
+        assert_eq!(config.for_type(&SuggestionType::LargeOutput).max_per_session, Some(5));
+        // Les autres plafonds restent par défaut
+        assert_eq!(config.for_type(&SuggestionType::BuildErrors).max_per_session, None);
+        assert_eq!(config.for_type(&SuggestionType::PromptReminder).max_per_session, Some(3));
+    }
+
+    #[test]
+    fn test_level_parse_accepts_known_levels_case_insensitively() {
+        assert_eq!(Level::parse("allow"), Some(Level::Allow));
+        assert_eq!(Level::parse("WARN"), Some(Level::Warn));
+        assert_eq!(Level::parse("Deny"), Some(Level::Deny));
+    }
+
+    #[test]
+    fn test_level_parse_rejects_unknown_level() {
+        assert_eq!(Level::parse("critical"), None);
+    }
+}
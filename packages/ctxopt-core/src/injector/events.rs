@@ -0,0 +1,253 @@
+//! Event sink pour le flux JSON d'évènements d'injection
+//!
+//! Modélisé sur le `JsonEmitter` de rustc: chaque décision de l'injecteur
+//! (suggestion émise ou injection ignorée) peut être sérialisée en un objet
+//! JSON par ligne pour que des outils externes (dashboards, scrapers CI)
+//! puissent observer le comportement de distill sans parser les messages
+//! ANSI destinés à l'humain.
+
+use super::templates::SuggestionType;
+use crate::stream::patterns::ContentType;
+
+/// Raison pour laquelle une injection candidate a été ignorée
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    /// L'intervalle minimum entre deux injections n'est pas écoulé
+    Throttled,
+    /// Ce type de suggestion a déjà été émis récemment
+    RecentlySuggested,
+    /// Les suggestions sont désactivées
+    Disabled,
+    /// Le `ContentType` ne correspond à aucune règle de déclenchement, ou le
+    /// plafond par session (`max_per_session`) de ce type est atteint
+    BelowThreshold,
+    /// La confiance (`Applicability`) de la suggestion est sous le seuil configuré
+    BelowConfidence,
+    /// Le type de suggestion est configuré à `Level::Allow` (supprimé explicitement)
+    ConfigSuppressed,
+}
+
+impl SkipReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::Throttled => "throttled",
+            SkipReason::RecentlySuggested => "recently_suggested",
+            SkipReason::Disabled => "disabled",
+            SkipReason::BelowThreshold => "below_threshold",
+            SkipReason::BelowConfidence => "below_confidence",
+            SkipReason::ConfigSuppressed => "config_suppressed",
+        }
+    }
+}
+
+/// Évènement émis à chaque évaluation de `generate_suggestion`
+#[derive(Debug, Clone)]
+pub enum InjectionEvent<'a> {
+    /// Une suggestion a été générée et sera injectée
+    Emitted {
+        content_type: &'a ContentType,
+        suggestion_type: &'a SuggestionType,
+        suggestions_count: usize,
+    },
+    /// L'injection a été écartée
+    Skipped {
+        content_type: &'a ContentType,
+        reason: SkipReason,
+        suggestions_count: usize,
+    },
+}
+
+/// Destination pour les évènements d'injection
+///
+/// `ContextInjector` appelle `record` à chaque décision; l'implémentation
+/// par défaut (`NoopSink`) ne fait rien, ce qui garde le chemin `should_inject`
+/// pur et sans coût quand aucun sink n'est configuré.
+pub trait EventSink: std::any::Any + Send {
+    /// Enregistre un évènement d'injection
+    fn record(&mut self, event: &InjectionEvent<'_>);
+
+    /// Permet de redescendre vers le type concret (ex: `JsonSink` dans les tests)
+    fn as_any(&self) -> &dyn std::any::Any;
+}
+
+/// Sink par défaut: ignore tous les évènements
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopSink;
+
+impl EventSink for NoopSink {
+    fn record(&mut self, _event: &InjectionEvent<'_>) {}
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Sink qui sérialise chaque évènement en une ligne JSON
+///
+/// Le format est volontairement plat (un objet par ligne, pas de tableau
+/// englobant) pour rester "greppable" et streamable sans parseur JSON
+/// complet côté consommateur.
+#[derive(Debug, Default)]
+pub struct JsonSink {
+    /// Lignes JSON accumulées, une par évènement
+    lines: Vec<String>,
+}
+
+impl JsonSink {
+    /// Crée un sink JSON vide
+    pub fn new() -> Self {
+        Self { lines: Vec::new() }
+    }
+
+    /// Retourne les lignes JSON accumulées jusqu'ici
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Vide le buffer de lignes accumulées
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    fn content_type_label(content_type: &ContentType) -> &'static str {
+        match content_type {
+            ContentType::BuildError { .. } => "build_error",
+            ContentType::FileRead { .. } => "file_read",
+            ContentType::LargeOutput { .. } => "large_output",
+            ContentType::PromptReady => "prompt_ready",
+            ContentType::Custom { .. } => "custom",
+            ContentType::Normal => "normal",
+        }
+    }
+
+    fn suggestion_type_label(suggestion_type: &SuggestionType) -> &'static str {
+        suggestion_type.as_str()
+    }
+}
+
+impl EventSink for JsonSink {
+    fn record(&mut self, event: &InjectionEvent<'_>) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+
+        let line = match event {
+            InjectionEvent::Emitted {
+                content_type,
+                suggestion_type,
+                suggestions_count,
+            } => format!(
+                "{{\"timestamp\":{},\"content_type\":\"{}\",\"suggestion_type\":\"{}\",\"skipped\":false,\"suggestions_count\":{}}}",
+                timestamp,
+                Self::content_type_label(content_type),
+                Self::suggestion_type_label(suggestion_type),
+                suggestions_count
+            ),
+            InjectionEvent::Skipped {
+                content_type,
+                reason,
+                suggestions_count,
+            } => format!(
+                "{{\"timestamp\":{},\"content_type\":\"{}\",\"skipped\":true,\"skip_reason\":\"{}\",\"suggestions_count\":{}}}",
+                timestamp,
+                Self::content_type_label(content_type),
+                reason.as_str(),
+                suggestions_count
+            ),
+        };
+
+        self.lines.push(line);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::patterns::BuildTool;
+
+    #[test]
+    fn test_emitted_payload() {
+        let mut sink = JsonSink::new();
+        let content_type = ContentType::BuildError {
+            error_count: 5,
+            tool: BuildTool::Rust,
+        };
+        let event = InjectionEvent::Emitted {
+            content_type: &content_type,
+            suggestion_type: &SuggestionType::BuildErrors,
+            suggestions_count: 1,
+        };
+        sink.record(&event);
+
+        let line = &sink.lines()[0];
+        assert!(line.contains("\"content_type\":\"build_error\""));
+        assert!(line.contains("\"suggestion_type\":\"build_errors\""));
+        assert!(line.contains("\"skipped\":false"));
+        assert!(line.contains("\"suggestions_count\":1"));
+    }
+
+    #[test]
+    fn test_skipped_payload_throttled() {
+        let mut sink = JsonSink::new();
+        let content_type = ContentType::LargeOutput { size: 20000 };
+        let event = InjectionEvent::Skipped {
+            content_type: &content_type,
+            reason: SkipReason::Throttled,
+            suggestions_count: 2,
+        };
+        sink.record(&event);
+
+        let line = &sink.lines()[0];
+        assert!(line.contains("\"skipped\":true"));
+        assert!(line.contains("\"skip_reason\":\"throttled\""));
+        assert!(line.contains("\"content_type\":\"large_output\""));
+    }
+
+    #[test]
+    fn test_skipped_payload_recently_suggested() {
+        let mut sink = JsonSink::new();
+        let content_type = ContentType::FileRead {
+            file_path: "src/main.rs".to_string(),
+        };
+        let event = InjectionEvent::Skipped {
+            content_type: &content_type,
+            reason: SkipReason::RecentlySuggested,
+            suggestions_count: 3,
+        };
+        sink.record(&event);
+
+        assert!(sink.lines()[0].contains("\"skip_reason\":\"recently_suggested\""));
+    }
+
+    #[test]
+    fn test_noop_sink_records_nothing() {
+        let mut sink = NoopSink;
+        let content_type = ContentType::Normal;
+        let event = InjectionEvent::Skipped {
+            content_type: &content_type,
+            reason: SkipReason::Disabled,
+            suggestions_count: 0,
+        };
+        // No assertions beyond "does not panic" - the point is zero overhead.
+        sink.record(&event);
+    }
+
+    #[test]
+    fn test_clear_resets_lines() {
+        let mut sink = JsonSink::new();
+        let content_type = ContentType::Normal;
+        sink.record(&InjectionEvent::Skipped {
+            content_type: &content_type,
+            reason: SkipReason::BelowThreshold,
+            suggestions_count: 0,
+        });
+        assert_eq!(sink.lines().len(), 1);
+        sink.clear();
+        assert!(sink.lines().is_empty());
+    }
+}
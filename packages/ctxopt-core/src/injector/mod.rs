@@ -3,7 +3,22 @@
 //! Injecte des suggestions dans le stdin du PTY
 //! quand des patterns optimisables sont détectés.
 
+pub(crate) mod config;
+pub(crate) mod events;
+pub(crate) mod profiler;
+pub(crate) mod registry;
+pub(crate) mod render;
+pub(crate) mod report;
 pub(crate) mod templates;
 pub(crate) mod triggers;
+pub(crate) mod verbosity;
 
-pub(crate) use triggers::ContextInjector;
+pub(crate) use config::{InjectorConfig, Level, SuggestionConfig};
+pub(crate) use events::{EventSink, InjectionEvent, JsonSink, NoopSink, SkipReason};
+pub(crate) use profiler::{ProfileSummary, SelfProfiler};
+pub(crate) use registry::explain;
+pub(crate) use render::{ColorCapability, SuggestionRenderer};
+pub(crate) use report::AnalysisReport;
+pub(crate) use templates::SuggestionReport;
+pub(crate) use triggers::{ContextInjector, KindCounters};
+pub(crate) use verbosity::{SuggestionVerbosity, VerbosityFilter};
@@ -0,0 +1,250 @@
+//! Self-profiling pour l'injecteur
+//!
+//! Inspiré du `SelfProfiler` de rustc: accumule des métriques sur les
+//! décisions de `ContextInjector` (combien de suggestions par type, combien
+//! de candidats supprimés et pourquoi, l'espacement entre deux injections, et
+//! une estimation des tokens économisés) pour donner une preuve concrète que
+//! les injections aident, ou au contraire que le throttle/l'anti-répétition
+//! sur-supprime.
+
+use super::events::SkipReason;
+use super::templates::{self, SuggestionType};
+use crate::tokens::TokenEstimator;
+use std::time::Duration;
+
+/// Compteurs par `SuggestionType`, un champ par variante plutôt qu'une
+/// `HashMap` puisque l'ensemble des types est fixe et petit.
+#[derive(Debug, Clone, Copy, Default)]
+struct TypeCounters {
+    build_errors: usize,
+    large_output: usize,
+    prompt_reminder: usize,
+    file_read: usize,
+}
+
+impl TypeCounters {
+    fn increment(&mut self, suggestion_type: &SuggestionType) {
+        *self.slot_mut(suggestion_type) += 1;
+    }
+
+    fn get(&self, suggestion_type: &SuggestionType) -> usize {
+        *self.slot(suggestion_type)
+    }
+
+    fn slot(&self, suggestion_type: &SuggestionType) -> &usize {
+        match suggestion_type {
+            SuggestionType::BuildErrors => &self.build_errors,
+            SuggestionType::LargeOutput => &self.large_output,
+            SuggestionType::PromptReminder => &self.prompt_reminder,
+            SuggestionType::FileRead => &self.file_read,
+        }
+    }
+
+    fn slot_mut(&mut self, suggestion_type: &SuggestionType) -> &mut usize {
+        match suggestion_type {
+            SuggestionType::BuildErrors => &mut self.build_errors,
+            SuggestionType::LargeOutput => &mut self.large_output,
+            SuggestionType::PromptReminder => &mut self.prompt_reminder,
+            SuggestionType::FileRead => &mut self.file_read,
+        }
+    }
+}
+
+/// Compteurs de suppression, un champ par `SkipReason`
+#[derive(Debug, Clone, Copy, Default)]
+struct SkipCounters {
+    throttled: usize,
+    recently_suggested: usize,
+    disabled: usize,
+    below_threshold: usize,
+    below_confidence: usize,
+    config_suppressed: usize,
+}
+
+impl SkipCounters {
+    fn increment(&mut self, reason: &SkipReason) {
+        let slot = match reason {
+            SkipReason::Throttled => &mut self.throttled,
+            SkipReason::RecentlySuggested => &mut self.recently_suggested,
+            SkipReason::Disabled => &mut self.disabled,
+            SkipReason::BelowThreshold => &mut self.below_threshold,
+            SkipReason::BelowConfidence => &mut self.below_confidence,
+            SkipReason::ConfigSuppressed => &mut self.config_suppressed,
+        };
+        *slot += 1;
+    }
+
+    fn total(&self) -> usize {
+        self.throttled
+            + self.recently_suggested
+            + self.disabled
+            + self.below_threshold
+            + self.below_confidence
+            + self.config_suppressed
+    }
+}
+
+/// Profileur léger accumulé par `ContextInjector` à chaque décision
+#[derive(Debug)]
+pub struct SelfProfiler {
+    injections: TypeCounters,
+    suppressed: SkipCounters,
+    last_gap: Option<Duration>,
+    tokens_saved: usize,
+    token_estimator: TokenEstimator,
+}
+
+impl SelfProfiler {
+    /// Crée un profileur vide
+    pub fn new() -> Self {
+        Self {
+            injections: TypeCounters::default(),
+            suppressed: SkipCounters::default(),
+            last_gap: None,
+            tokens_saved: 0,
+            token_estimator: TokenEstimator::new(),
+        }
+    }
+
+    /// Enregistre une suggestion effectivement émise
+    pub fn record_emitted(&mut self, suggestion_type: &SuggestionType, gap: Duration) {
+        self.injections.increment(suggestion_type);
+        self.last_gap = Some(gap);
+        self.tokens_saved += templates::estimated_tokens_saved(suggestion_type, &self.token_estimator);
+    }
+
+    /// Enregistre une candidate d'injection supprimée, avec la raison
+    pub fn record_suppressed(&mut self, reason: &SkipReason) {
+        self.suppressed.increment(reason);
+    }
+
+    /// Produit un résumé agrégé, prêt à être affiché en fin de session
+    pub fn summary(&self) -> ProfileSummary {
+        ProfileSummary {
+            build_errors_injections: self.injections.get(&SuggestionType::BuildErrors),
+            large_output_injections: self.injections.get(&SuggestionType::LargeOutput),
+            prompt_reminder_injections: self.injections.get(&SuggestionType::PromptReminder),
+            file_read_injections: self.injections.get(&SuggestionType::FileRead),
+            suppressed_throttled: self.suppressed.throttled,
+            suppressed_recently_suggested: self.suppressed.recently_suggested,
+            suppressed_disabled: self.suppressed.disabled,
+            suppressed_below_threshold: self.suppressed.below_threshold,
+            suppressed_below_confidence: self.suppressed.below_confidence,
+            suppressed_config_suppressed: self.suppressed.config_suppressed,
+            total_suppressed: self.suppressed.total(),
+            last_injection_gap_ms: self.last_gap.map(|d| d.as_millis() as u64),
+            estimated_tokens_saved: self.tokens_saved,
+        }
+    }
+}
+
+impl Default for SelfProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Instantané du profileur, dumpable en fin de session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileSummary {
+    pub build_errors_injections: usize,
+    pub large_output_injections: usize,
+    pub prompt_reminder_injections: usize,
+    pub file_read_injections: usize,
+    pub suppressed_throttled: usize,
+    pub suppressed_recently_suggested: usize,
+    pub suppressed_disabled: usize,
+    pub suppressed_below_threshold: usize,
+    pub suppressed_below_confidence: usize,
+    pub suppressed_config_suppressed: usize,
+    pub total_suppressed: usize,
+    pub last_injection_gap_ms: Option<u64>,
+    pub estimated_tokens_saved: usize,
+}
+
+impl ProfileSummary {
+    /// Sérialise le résumé en une ligne JSON
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"build_errors_injections\":{},\"large_output_injections\":{},\"prompt_reminder_injections\":{},\"file_read_injections\":{},\"suppressed_throttled\":{},\"suppressed_recently_suggested\":{},\"suppressed_disabled\":{},\"suppressed_below_threshold\":{},\"suppressed_below_confidence\":{},\"suppressed_config_suppressed\":{},\"total_suppressed\":{},\"last_injection_gap_ms\":{},\"estimated_tokens_saved\":{}}}",
+            self.build_errors_injections,
+            self.large_output_injections,
+            self.prompt_reminder_injections,
+            self.file_read_injections,
+            self.suppressed_throttled,
+            self.suppressed_recently_suggested,
+            self.suppressed_disabled,
+            self.suppressed_below_threshold,
+            self.suppressed_below_confidence,
+            self.suppressed_config_suppressed,
+            self.total_suppressed,
+            self.last_injection_gap_ms.map_or("null".to_string(), |ms| ms.to_string()),
+            self.estimated_tokens_saved,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_emitted_increments_per_type() {
+        let mut profiler = SelfProfiler::new();
+        profiler.record_emitted(&SuggestionType::BuildErrors, Duration::from_secs(5));
+        profiler.record_emitted(&SuggestionType::BuildErrors, Duration::from_secs(7));
+        profiler.record_emitted(&SuggestionType::LargeOutput, Duration::from_secs(2));
+
+        let summary = profiler.summary();
+        assert_eq!(summary.build_errors_injections, 2);
+        assert_eq!(summary.large_output_injections, 1);
+        assert_eq!(summary.prompt_reminder_injections, 0);
+    }
+
+    #[test]
+    fn test_record_suppressed_tracks_reason() {
+        let mut profiler = SelfProfiler::new();
+        profiler.record_suppressed(&SkipReason::Throttled);
+        profiler.record_suppressed(&SkipReason::Throttled);
+        profiler.record_suppressed(&SkipReason::RecentlySuggested);
+
+        let summary = profiler.summary();
+        assert_eq!(summary.suppressed_throttled, 2);
+        assert_eq!(summary.suppressed_recently_suggested, 1);
+        assert_eq!(summary.total_suppressed, 3);
+    }
+
+    #[test]
+    fn test_last_injection_gap_tracks_most_recent() {
+        let mut profiler = SelfProfiler::new();
+        assert_eq!(profiler.summary().last_injection_gap_ms, None);
+
+        profiler.record_emitted(&SuggestionType::FileRead, Duration::from_millis(250));
+        assert_eq!(profiler.summary().last_injection_gap_ms, Some(250));
+
+        profiler.record_emitted(&SuggestionType::FileRead, Duration::from_millis(400));
+        assert_eq!(profiler.summary().last_injection_gap_ms, Some(400));
+    }
+
+    #[test]
+    fn test_tokens_saved_accumulates_for_compressible_types() {
+        let mut profiler = SelfProfiler::new();
+        profiler.record_emitted(&SuggestionType::PromptReminder, Duration::from_secs(1));
+        assert_eq!(profiler.summary().estimated_tokens_saved, 0);
+
+        profiler.record_emitted(&SuggestionType::LargeOutput, Duration::from_secs(1));
+        assert!(profiler.summary().estimated_tokens_saved > 0);
+    }
+
+    #[test]
+    fn test_summary_to_json_contains_all_fields() {
+        let mut profiler = SelfProfiler::new();
+        profiler.record_emitted(&SuggestionType::BuildErrors, Duration::from_secs(1));
+        profiler.record_suppressed(&SkipReason::BelowThreshold);
+
+        let json = profiler.summary().to_json();
+        assert!(json.contains("\"build_errors_injections\":1"));
+        assert!(json.contains("\"suppressed_below_threshold\":1"));
+        assert!(json.contains("\"last_injection_gap_ms\":1000"));
+    }
+}
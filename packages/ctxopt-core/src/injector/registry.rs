@@ -0,0 +1,96 @@
+//! Registre des codes de suggestion
+//!
+//! Mirroring `DiagnosticId`/`Registry` de rustc: chaque `SuggestionType` a un
+//! identifiant stable (`D0001`, `D0002`, ...) qui reste inchangé d'une version
+//! à l'autre, et une explication plus longue récupérable via `explain`. Cela
+//! rend les logs et le flux JSON greppables, et prépare une future commande
+//! `distill explain D0002`.
+
+use super::templates::SuggestionType;
+
+/// Code stable attribué à un type de suggestion
+pub fn code_for(suggestion_type: &SuggestionType) -> &'static str {
+    match suggestion_type {
+        SuggestionType::BuildErrors => "D0001",
+        SuggestionType::LargeOutput => "D0002",
+        SuggestionType::FileRead => "D0003",
+        SuggestionType::PromptReminder => "D0004",
+    }
+}
+
+/// Explication longue associée à un code, expliquant pourquoi distill a
+/// injecté ce contexte et ce que l'utilisateur peut en faire.
+///
+/// Chaque explication tient en deux paragraphes séparés par une ligne vide
+/// (comme le `--explain` de rustc): le contexte de détection, puis la
+/// commande d'optimisation MCP recommandée. `utils::explain` expose ce même
+/// texte côté Node.js pour un "why?" à la demande dans une UI.
+pub fn explain(code: &str) -> Option<&'static str> {
+    match code {
+        "D0001" => Some(
+            "distill a détecté plusieurs erreurs de build consécutives dans le flux. \
+             Au-delà du seuil configuré, cela signale généralement un lot d'erreurs \
+             qu'il vaut mieux résumer que renvoyer tel quel à Claude.\n\n\
+             Recommandé: mcp__ctxopt__auto_optimize, qui économise généralement 95%+ \
+             des tokens sur ce type de lot.",
+        ),
+        "D0002" => Some(
+            "Le flux a dépassé la taille configurée pour un \"gros output\" (ex: un \
+             dump de logs ou un test verbeux), qui gonfle le contexte sans apporter \
+             d'information supplémentaire proportionnelle.\n\n\
+             Recommandé: mcp__ctxopt__compress_context, qui résume ce contenu avec \
+             40-60% d'économie de tokens tout en gardant les lignes actionnables.",
+        ),
+        "D0003" => Some(
+            "Un fichier de code a été lu en entier dans le flux, alors que Claude n'a \
+             souvent besoin que d'une partie de son contenu pour la tâche en cours.\n\n\
+             Recommandé: mcp__ctxopt__smart_file_read, qui peut en extraire uniquement \
+             les portions pertinentes, pour 50-70% d'économie de tokens.",
+        ),
+        "D0004" => Some(
+            "Claude est revenu à un prompt vide sans qu'aucune optimisation n'ait été \
+             invoquée récemment dans cette session.\n\n\
+             Recommandé: un rappel léger des outils MCP disponibles \
+             (smart_file_read, auto_optimize, compress_context) pour garder la \
+             session efficace.",
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_for_is_stable() {
+        assert_eq!(code_for(&SuggestionType::BuildErrors), "D0001");
+        assert_eq!(code_for(&SuggestionType::LargeOutput), "D0002");
+        assert_eq!(code_for(&SuggestionType::FileRead), "D0003");
+        assert_eq!(code_for(&SuggestionType::PromptReminder), "D0004");
+    }
+
+    #[test]
+    fn test_explain_known_code() {
+        assert!(explain("D0001").unwrap().contains("auto_optimize"));
+        assert!(explain("D0002").unwrap().contains("compress_context"));
+    }
+
+    #[test]
+    fn test_explain_unknown_code_returns_none() {
+        assert!(explain("D9999").is_none());
+    }
+
+    #[test]
+    fn test_every_code_has_an_explanation() {
+        for suggestion_type in [
+            SuggestionType::BuildErrors,
+            SuggestionType::LargeOutput,
+            SuggestionType::FileRead,
+            SuggestionType::PromptReminder,
+        ] {
+            let code = code_for(&suggestion_type);
+            assert!(explain(code).is_some(), "missing explanation for {code}");
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Détection de capacité ANSI et rendu adaptatif des suggestions
+//!
+//! Les constructeurs de `Suggestion` (voir `templates`) embarquent des
+//! séquences d'échappement brutes (`\x1b[33m`, `\x1b[36m`, ...), qui
+//! corrompent l'affichage quand stdout est redirigé vers un fichier de log,
+//! capturé par un autre programme, ou affiché sur un terminal sans support
+//! couleur. `SuggestionRenderer` reprend l'idée de base de données de
+//! capacités de terminfo: on détecte une fois l'environnement (`NO_COLOR`,
+//! `TERM=dumb`, et si le flux cible est un TTY) et on choisit un niveau de
+//! rendu, plutôt que de laisser chaque appelant décider au cas par cas.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io::IsTerminal;
+
+/// Séquences `\x1b[9Xm` (couleurs vives) à replier sur leur équivalent
+/// standard `\x1b[3Xm` en capacité `Basic`
+static BRIGHT_COLOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"\x1b\[9([0-7])m").unwrap());
+
+/// Toute séquence d'échappement ANSI, retirée en capacité `Plain`
+static ANSI_ESCAPE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\x1b\[[0-9;]*[a-zA-Z]").unwrap());
+
+/// Niveau de capacité ANSI du flux de sortie cible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorCapability {
+    /// Terminal complet: les séquences ANSI (couleurs vives incluses) passent telles quelles
+    Full,
+    /// Terminal restreint à la palette 8 couleurs standard: les couleurs
+    /// vives sont repliées sur leur équivalent non-vif le plus proche
+    Basic,
+    /// Pas de couleur: toutes les séquences ANSI sont retirées
+    Plain,
+}
+
+impl ColorCapability {
+    /// Détecte la capacité du terminal courant
+    ///
+    /// `NO_COLOR` (voir <https://no-color.org>) et `TERM=dumb` forcent
+    /// `Plain`, tout comme un stdout qui n'est pas un TTY (pipe, fichier de
+    /// log, capture par un autre programme). `TERM=linux` (console Linux
+    /// brute, sans couleurs vives) et un `TERM` vide retombent sur `Basic`;
+    /// le reste assume un terminal moderne (`Full`).
+    pub fn detect() -> Self {
+        if std::env::var_os("NO_COLOR").is_some() {
+            return Self::Plain;
+        }
+
+        let term = std::env::var("TERM").unwrap_or_default();
+        if term == "dumb" {
+            return Self::Plain;
+        }
+
+        if !std::io::stdout().is_terminal() {
+            return Self::Plain;
+        }
+
+        if term == "linux" || term.is_empty() {
+            Self::Basic
+        } else {
+            Self::Full
+        }
+    }
+}
+
+/// Rend un `Suggestion::display_message` selon la capacité ANSI détectée
+pub struct SuggestionRenderer {
+    capability: ColorCapability,
+}
+
+impl SuggestionRenderer {
+    /// Détecte la capacité de l'environnement courant
+    pub fn new() -> Self {
+        Self {
+            capability: ColorCapability::detect(),
+        }
+    }
+
+    /// Force un niveau de capacité, pour les tests ou un mode CLI explicite
+    /// (ex: `--color=never`)
+    pub fn forced(capability: ColorCapability) -> Self {
+        Self { capability }
+    }
+
+    /// Adapte un message au niveau de capacité de ce renderer
+    pub fn render(&self, message: &str) -> String {
+        match self.capability {
+            ColorCapability::Full => message.to_string(),
+            ColorCapability::Basic => BRIGHT_COLOR.replace_all(message, "\x1b[3${1}m").into_owned(),
+            ColorCapability::Plain => ANSI_ESCAPE.replace_all(message, "").into_owned(),
+        }
+    }
+}
+
+impl Default for SuggestionRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_capability_leaves_message_untouched() {
+        let renderer = SuggestionRenderer::forced(ColorCapability::Full);
+        let message = "\x1b[33m[ctxopt]\x1b[0m hello";
+        assert_eq!(renderer.render(message), message);
+    }
+
+    #[test]
+    fn test_basic_capability_downgrades_bright_colors() {
+        let renderer = SuggestionRenderer::forced(ColorCapability::Basic);
+        let rendered = renderer.render("\x1b[90mdim\x1b[0m");
+        assert_eq!(rendered, "\x1b[30mdim\x1b[0m");
+    }
+
+    #[test]
+    fn test_plain_capability_strips_all_ansi() {
+        let renderer = SuggestionRenderer::forced(ColorCapability::Plain);
+        let rendered = renderer.render("\x1b[33m[ctxopt]\x1b[0m hello");
+        assert_eq!(rendered, "[ctxopt] hello");
+        assert!(!rendered.contains('\x1b'), "Should not contain ANSI codes");
+    }
+}
@@ -0,0 +1,88 @@
+//! Rapport JSON combiné d'un appel `analyze`, au niveau bibliothèque
+//!
+//! `AnalysisEvent` (voir `stream::events`) et `SuggestionReport` (voir
+//! `templates`) sont chacun sérialisables indépendamment, mais un wrapper qui
+//! veut savoir en une seule ligne "qu'est-ce qui a été détecté et qu'est-ce
+//! qui a été suggéré" pour un chunk doit aujourd'hui recoller les deux
+//! lui-même. `AnalysisReport` les combine en un seul objet, façon rapport
+//! `cargo --message-format=json`, sans dépendre des bindings NAPI (utilisable
+//! directement par les consommateurs Rust de la feature `bench`).
+
+use super::templates::SuggestionReport;
+use crate::stream::analyzer::AnalysisResult;
+use crate::stream::events::ContentTypeEvent;
+use serde::Serialize;
+
+/// Rapport auto-descriptif combinant la détection et les suggestions d'un
+/// appel `StreamAnalyzer::analyze`
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisReport {
+    /// Types de contenu détectés pour ce chunk
+    pub content_types: Vec<ContentTypeEvent>,
+
+    /// Estimation de tokens pour ce chunk
+    pub token_estimate: usize,
+
+    /// Suggestions générées pour ce chunk, dans l'ordre de `content_types`
+    pub suggestions: Vec<SuggestionReport>,
+}
+
+impl AnalysisReport {
+    /// Construit le rapport à partir d'un résultat d'analyse et des
+    /// suggestions déjà converties en `SuggestionReport` (voir `Suggestion::to_report`)
+    pub fn new(result: &AnalysisResult, suggestions: Vec<SuggestionReport>) -> Self {
+        Self {
+            content_types: result.content_types.iter().map(ContentTypeEvent::from).collect(),
+            token_estimate: result.token_estimate,
+            suggestions,
+        }
+    }
+
+    /// Sérialise le rapport en une ligne JSON (format newline-delimited)
+    pub fn to_json_line(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::injector::templates::Suggestion;
+    use crate::stream::analyzer::StreamAnalyzer;
+    use crate::stream::patterns::ContentType;
+
+    #[test]
+    fn test_analysis_report_combines_content_types_and_suggestions() {
+        let mut analyzer = StreamAnalyzer::new();
+        let result = analyzer.analyze(b"error TS2304: Cannot find name 'foo'");
+
+        let suggestion_reports: Vec<SuggestionReport> = result
+            .content_types
+            .iter()
+            .filter_map(|ct| match ct {
+                ContentType::BuildError { error_count, tool } => {
+                    Some(Suggestion::build_errors(*error_count, *tool).to_report(ct))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let report = AnalysisReport::new(&result, suggestion_reports);
+        assert_eq!(report.token_estimate, result.token_estimate);
+        assert_eq!(report.suggestions.len(), 1);
+        assert_eq!(report.suggestions[0].suggestion_type, "build_errors");
+    }
+
+    #[test]
+    fn test_analysis_report_round_trips_through_json() {
+        let mut analyzer = StreamAnalyzer::new();
+        let result = analyzer.analyze(b"Reading file: src/main.ts");
+
+        let report = AnalysisReport::new(&result, Vec::new());
+        let line = report.to_json_line().unwrap();
+
+        assert!(line.contains("\"token_estimate\""));
+        assert!(line.contains("\"content_types\""));
+        assert!(line.contains("\"suggestions\":[]"));
+    }
+}
@@ -3,7 +3,11 @@
 //! Génère des messages colorés ANSI pour suggérer les outils MCP
 //! en fonction du type de contenu détecté.
 
-use crate::stream::patterns::BuildTool;
+use super::registry::code_for;
+use super::render::SuggestionRenderer;
+use crate::stream::patterns::{BuildTool, ContentType};
+use crate::tokens::TokenEstimator;
+use serde::Serialize;
 
 /// Type de suggestion
 #[derive(Debug, Clone, PartialEq)]
@@ -18,6 +22,66 @@ pub enum SuggestionType {
     FileRead,
 }
 
+impl SuggestionType {
+    /// Parse un type de suggestion depuis son libellé `snake_case` (celui du
+    /// flux JSON de l'injecteur, voir `JsonSink`). Utilisé par
+    /// `CtxOptSession::set_suggestion_level` pour accepter un `kind` passé
+    /// depuis Node.js.
+    pub(crate) fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "build_errors" => Some(Self::BuildErrors),
+            "large_output" => Some(Self::LargeOutput),
+            "file_read" => Some(Self::FileRead),
+            "prompt_reminder" => Some(Self::PromptReminder),
+            _ => None,
+        }
+    }
+
+    /// Type de suggestion correspondant à un `ContentType::kind()`, pour
+    /// estimer les tokens économisés par bucket dans
+    /// `CtxOptSession::stats_breakdown`. Distinct de `parse`: les libellés
+    /// de `ContentType::kind()` (ex: `"build_error"`, `"prompt_ready"`) ne
+    /// sont pas ceux du flux JSON de l'injecteur (ex: `"build_errors"`,
+    /// `"prompt_reminder"`), et `Custom`/`Normal` n'ont pas de suggestion associée.
+    pub(crate) fn from_content_kind(kind: &str) -> Option<Self> {
+        match kind {
+            "build_error" => Some(Self::BuildErrors),
+            "large_output" => Some(Self::LargeOutput),
+            "file_read" => Some(Self::FileRead),
+            "prompt_ready" => Some(Self::PromptReminder),
+            _ => None,
+        }
+    }
+
+    /// Libellé `snake_case` stable, inverse de `parse`. Partagé par
+    /// `JsonSink` et par `Suggestion::to_json` pour que le tag `type` du
+    /// flux JSON reste identique des deux côtés.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::BuildErrors => "build_errors",
+            Self::LargeOutput => "large_output",
+            Self::FileRead => "file_read",
+            Self::PromptReminder => "prompt_reminder",
+        }
+    }
+}
+
+/// Niveau de confiance d'une suggestion, inspiré de l'`Applicability` de rustc
+///
+/// Permet à `ContextInjector` de filtrer les suggestions peu fiables quand
+/// un `min_applicability` est configuré, pour un mode "high-confidence only".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Applicability {
+    /// Aucune confiance particulière - nudge générique
+    Unspecified,
+    /// Il manque du contexte pour être sûr, mais la piste est plausible
+    HasPlaceholders,
+    /// Probablement correct, à vérifier
+    MaybeIncorrect,
+    /// La suggestion est sûre et actionnable telle quelle
+    MachineApplicable,
+}
+
 /// Suggestion générée
 #[derive(Debug, Clone)]
 pub struct Suggestion {
@@ -26,11 +90,64 @@ pub struct Suggestion {
 
     /// Message à afficher (pas injecté dans stdin)
     pub display_message: String,
+
+    /// Niveau de confiance de la suggestion
+    pub applicability: Applicability,
+
+    /// Code stable (ex: `D0001`), greppable dans les logs et le flux JSON
+    pub code: &'static str,
+}
+
+/// Représentation JSON plate d'une [`Suggestion`], voir [`Suggestion::to_json`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuggestionReport {
+    /// Tag stable du type de suggestion (voir `SuggestionType::as_str`)
+    #[serde(rename = "type")]
+    pub suggestion_type: &'static str,
+
+    /// Code stable (ex: `D0001`), voir `registry::explain`
+    pub code: &'static str,
+
+    /// Message destiné à l'humain (conserve les codes ANSI)
+    pub message: String,
+
+    /// Nombre d'erreurs détectées, présent pour `type: "build_errors"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_count: Option<usize>,
+
+    /// Outil de build détecté, présent pour `type: "build_errors"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+
+    /// Taille en octets de l'output, présent pour `type: "large_output"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<usize>,
+
+    /// Chemin du fichier lu, présent pour `type: "file_read"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+}
+
+impl Applicability {
+    /// Confiance pour une suggestion d'erreurs de build, à partir du
+    /// nombre d'erreurs et de l'outil détecté. Partagé par `Suggestion::build_errors`
+    /// et par `ContextInjector` pour filtrer sans construire la suggestion complète.
+    pub fn for_build_error(error_count: usize, tool: BuildTool) -> Self {
+        if error_count >= 10 && !matches!(tool, BuildTool::Generic) {
+            Applicability::MachineApplicable
+        } else if matches!(tool, BuildTool::Generic) {
+            Applicability::MaybeIncorrect
+        } else {
+            Applicability::HasPlaceholders
+        }
+    }
 }
 
 impl Suggestion {
     /// Crée une suggestion pour erreurs de build
     pub fn build_errors(error_count: usize, tool: BuildTool) -> Self {
+        let applicability = Applicability::for_build_error(error_count, tool);
+
         Self {
             suggestion_type: SuggestionType::BuildErrors,
             display_message: format!(
@@ -39,6 +156,8 @@ impl Suggestion {
                 error_count,
                 tool.as_str()
             ),
+            applicability,
+            code: code_for(&SuggestionType::BuildErrors),
         }
     }
 
@@ -52,6 +171,9 @@ impl Suggestion {
                  Use \x1b[36mmcp__ctxopt__compress_context\x1b[0m for 40-60% savings.",
                 size_kb
             ),
+            // Un gros output ne dit rien sur s'il est réellement compressible.
+            applicability: Applicability::Unspecified,
+            code: code_for(&SuggestionType::LargeOutput),
         }
     }
 
@@ -62,6 +184,24 @@ impl Suggestion {
             display_message:
                 "\x1b[90m[ctxopt] MCP tools: smart_file_read, auto_optimize, compress_context\x1b[0m"
                     .to_string(),
+            applicability: Applicability::Unspecified,
+            code: code_for(&SuggestionType::PromptReminder),
+        }
+    }
+
+    /// Crée un rappel de mode watch: la commande wrappée vient d'être
+    /// relancée après des modifications de fichiers (voir `pty::watch`).
+    /// Même style léger que [`Self::prompt_reminder`] plutôt qu'une alerte,
+    /// puisqu'un restart en mode watch est attendu, pas une anomalie.
+    pub fn watch_restart(changed_files: usize) -> Self {
+        Self {
+            suggestion_type: SuggestionType::PromptReminder,
+            display_message: format!(
+                "\x1b[90m[ctxopt] Restarted after {changed_files} file change(s). \
+                 MCP tools: smart_file_read, auto_optimize, compress_context\x1b[0m"
+            ),
+            applicability: Applicability::Unspecified,
+            code: code_for(&SuggestionType::PromptReminder),
         }
     }
 
@@ -74,17 +214,103 @@ impl Suggestion {
                  Consider \x1b[36mmcp__ctxopt__smart_file_read\x1b[0m for 50-70% savings.",
                 file_path
             ),
+            applicability: Applicability::HasPlaceholders,
+            code: code_for(&SuggestionType::FileRead),
         }
     }
 
-    /// Formatte le message pour affichage terminal
+    /// Formatte le message pour affichage terminal, en détectant la
+    /// capacité ANSI de l'environnement courant (voir `SuggestionRenderer`)
     pub fn format_for_display(&self) -> String {
-        format!("\n{}\n", self.display_message)
+        self.format_for_display_with(&SuggestionRenderer::new())
+    }
+
+    /// Comme `format_for_display`, mais avec un `SuggestionRenderer` fourni
+    /// par l'appelant (forcé à un niveau de capacité donné dans les tests,
+    /// ou piloté par un flag CLI `--color` côté wrapper)
+    pub fn format_for_display_with(&self, renderer: &SuggestionRenderer) -> String {
+        format!("\n{}\n", renderer.render(&self.display_message))
+    }
+
+    /// Construit la représentation JSON plate de cette suggestion, façon
+    /// formatteur JSON de `libtest`/`deno test`: un objet avec un tag `type`
+    /// stable (voir `SuggestionType::as_str`) et les champs contextuels du
+    /// `ContentType` qui l'a déclenchée (`error_count`/`tool`, `size_bytes`
+    /// ou `file_path`), pour qu'un outil externe sache exactement quelle
+    /// optimisation MCP a été suggérée et pourquoi sans parser
+    /// `display_message` (décoré ANSI, destiné à l'humain).
+    pub fn to_report(&self, content_type: &ContentType) -> SuggestionReport {
+        let (error_count, tool, size_bytes, file_path) = match content_type {
+            ContentType::BuildError { error_count, tool } => {
+                (Some(*error_count), Some(tool.as_str().to_string()), None, None)
+            }
+            ContentType::LargeOutput { size } => (None, None, Some(*size), None),
+            ContentType::FileRead { file_path } => (None, None, None, Some(file_path.clone())),
+            ContentType::PromptReady | ContentType::Custom { .. } | ContentType::Normal => {
+                (None, None, None, None)
+            }
+        };
+
+        SuggestionReport {
+            suggestion_type: self.suggestion_type.as_str(),
+            code: self.code,
+            message: self.display_message.clone(),
+            error_count,
+            tool,
+            size_bytes,
+            file_path,
+        }
+    }
+
+    /// Sérialise [`Self::to_report`] en une ligne JSON (format newline-delimited)
+    pub fn to_json(&self, content_type: &ContentType) -> serde_json::Result<String> {
+        serde_json::to_string(&self.to_report(content_type))
+    }
+
+    /// Formatte la suggestion façon `AnnotateSnippetEmitterWriter` de rustc:
+    /// les lignes de `context` qui ont déclenché la détection, citées et
+    /// soulignées d'un rang de carets, suivies du message. `context` vient
+    /// typiquement de `StreamAnalyzer::recent_lines`/`SharedAnalyzer::recent_lines`.
+    pub fn format_annotated(&self, context: &str) -> String {
+        let width = context.lines().map(str::len).max().unwrap_or(0);
+
+        let mut out = String::from("\n");
+        for line in context.lines() {
+            out.push_str("  | ");
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str("  | ");
+        out.push_str(&"^".repeat(width));
+        out.push('\n');
+        out.push_str(&format!("  = {}\n", self.display_message));
+        out
+    }
+}
+
+/// Estimation heuristique des tokens économisés par une suggestion de ce type.
+///
+/// `LargeOutput`/`BuildErrors` sont les deux cas où distill recommande une
+/// compression active (`compress_context`/`auto_optimize`); les autres
+/// types sont des rappels sans économie de tokens directe. Partagée par
+/// `SelfProfiler` (agrégation en fin de session) et par le flux JSON de
+/// `CtxOptSession::read_json` (champ `tokens` par évènement `suggestion`).
+pub(crate) fn estimated_tokens_saved(
+    suggestion_type: &SuggestionType,
+    estimator: &TokenEstimator,
+) -> usize {
+    match suggestion_type {
+        // ~50% d'économie moyenne annoncée pour compress_context sur 10KB de sortie.
+        SuggestionType::LargeOutput => estimator.estimate(&"x".repeat(10_000)) / 2,
+        // ~95% d'économie annoncée pour auto_optimize sur un lot d'erreurs typique.
+        SuggestionType::BuildErrors => estimator.estimate(&"error: x\n".repeat(20)) * 95 / 100,
+        SuggestionType::FileRead | SuggestionType::PromptReminder => 0,
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::render::ColorCapability;
     use super::*;
 
     #[test]
@@ -95,6 +321,37 @@ mod tests {
         assert!(suggestion.display_message.contains("tsc"));
     }
 
+    #[test]
+    fn test_build_errors_applicability_high_confidence() {
+        let suggestion = Suggestion::build_errors(12, BuildTool::Rust);
+        assert_eq!(suggestion.applicability, Applicability::MachineApplicable);
+    }
+
+    #[test]
+    fn test_build_errors_applicability_generic_tool() {
+        let suggestion = Suggestion::build_errors(50, BuildTool::Generic);
+        assert_eq!(suggestion.applicability, Applicability::MaybeIncorrect);
+    }
+
+    #[test]
+    fn test_build_errors_applicability_low_count() {
+        let suggestion = Suggestion::build_errors(3, BuildTool::Rust);
+        assert_eq!(suggestion.applicability, Applicability::HasPlaceholders);
+    }
+
+    #[test]
+    fn test_large_output_applicability_is_unspecified() {
+        let suggestion = Suggestion::large_output(20000);
+        assert_eq!(suggestion.applicability, Applicability::Unspecified);
+    }
+
+    #[test]
+    fn test_applicability_ordering() {
+        assert!(Applicability::Unspecified < Applicability::HasPlaceholders);
+        assert!(Applicability::HasPlaceholders < Applicability::MaybeIncorrect);
+        assert!(Applicability::MaybeIncorrect < Applicability::MachineApplicable);
+    }
+
     #[test]
     fn test_large_output_suggestion() {
         let suggestion = Suggestion::large_output(10240);
@@ -109,6 +366,14 @@ mod tests {
         assert!(suggestion.display_message.contains("smart_file_read"));
     }
 
+    #[test]
+    fn test_watch_restart_suggestion() {
+        let suggestion = Suggestion::watch_restart(3);
+        assert_eq!(suggestion.suggestion_type, SuggestionType::PromptReminder);
+        assert!(suggestion.display_message.contains("3 file change(s)"));
+        assert!(suggestion.display_message.contains("smart_file_read"));
+    }
+
     #[test]
     fn test_file_read_suggestion() {
         let suggestion = Suggestion::file_read("src/main.ts");
@@ -123,4 +388,103 @@ mod tests {
         assert!(formatted.starts_with('\n'));
         assert!(formatted.ends_with('\n'));
     }
+
+    #[test]
+    fn test_suggestion_codes_are_stable() {
+        assert_eq!(Suggestion::build_errors(42, BuildTool::TypeScript).code, "D0001");
+        assert_eq!(Suggestion::large_output(20000).code, "D0002");
+        assert_eq!(Suggestion::file_read("src/main.ts").code, "D0003");
+        assert_eq!(Suggestion::prompt_reminder().code, "D0004");
+    }
+
+    #[test]
+    fn test_suggestion_type_parse_round_trips_known_kinds() {
+        assert_eq!(SuggestionType::parse("build_errors"), Some(SuggestionType::BuildErrors));
+        assert_eq!(SuggestionType::parse("large_output"), Some(SuggestionType::LargeOutput));
+        assert_eq!(SuggestionType::parse("file_read"), Some(SuggestionType::FileRead));
+        assert_eq!(SuggestionType::parse("prompt_reminder"), Some(SuggestionType::PromptReminder));
+    }
+
+    #[test]
+    fn test_suggestion_type_parse_rejects_unknown_kind() {
+        assert_eq!(SuggestionType::parse("bogus"), None);
+    }
+
+    #[test]
+    fn test_format_annotated_underlines_widest_line() {
+        let suggestion = Suggestion::build_errors(3, BuildTool::Rust);
+        let formatted = suggestion.format_annotated("short\na much longer line");
+
+        assert!(formatted.contains("  | short"));
+        assert!(formatted.contains("  | a much longer line"));
+        assert!(formatted.contains(&"^".repeat("a much longer line".len())));
+        assert!(formatted.contains(&suggestion.display_message));
+    }
+
+    #[test]
+    fn test_format_annotated_empty_context_has_no_carets() {
+        let suggestion = Suggestion::prompt_reminder();
+        let formatted = suggestion.format_annotated("");
+
+        assert!(formatted.contains("  = "));
+        assert!(!formatted.contains('^'));
+    }
+
+    #[test]
+    fn test_format_for_display_with_plain_capability_strips_ansi() {
+        let suggestion = Suggestion::prompt_reminder();
+        let renderer = SuggestionRenderer::forced(ColorCapability::Plain);
+        let formatted = suggestion.format_for_display_with(&renderer);
+
+        assert!(!formatted.contains('\x1b'), "Should not contain ANSI codes");
+        assert!(formatted.contains("smart_file_read"));
+    }
+
+    #[test]
+    fn test_format_for_display_with_full_capability_keeps_ansi() {
+        let suggestion = Suggestion::build_errors(42, BuildTool::Rust);
+        let renderer = SuggestionRenderer::forced(ColorCapability::Full);
+        let formatted = suggestion.format_for_display_with(&renderer);
+
+        assert!(formatted.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_to_json_build_errors_includes_error_count_and_tool() {
+        let suggestion = Suggestion::build_errors(42, BuildTool::TypeScript);
+        let content_type = ContentType::BuildError {
+            error_count: 42,
+            tool: BuildTool::TypeScript,
+        };
+        let line = suggestion.to_json(&content_type).unwrap();
+
+        assert!(line.contains("\"type\":\"build_errors\""));
+        assert!(line.contains("\"error_count\":42"));
+        assert!(line.contains("\"tool\":\"tsc\""));
+        assert!(line.contains("\"code\":\"D0001\""));
+        assert!(!line.contains("\"size_bytes\""));
+    }
+
+    #[test]
+    fn test_to_json_large_output_includes_size_bytes() {
+        let suggestion = Suggestion::large_output(20480);
+        let content_type = ContentType::LargeOutput { size: 20480 };
+        let line = suggestion.to_json(&content_type).unwrap();
+
+        assert!(line.contains("\"type\":\"large_output\""));
+        assert!(line.contains("\"size_bytes\":20480"));
+        assert!(!line.contains("\"error_count\""));
+    }
+
+    #[test]
+    fn test_to_json_file_read_includes_file_path() {
+        let suggestion = Suggestion::file_read("src/main.ts");
+        let content_type = ContentType::FileRead {
+            file_path: "src/main.ts".to_string(),
+        };
+        let line = suggestion.to_json(&content_type).unwrap();
+
+        assert!(line.contains("\"type\":\"file_read\""));
+        assert!(line.contains("\"file_path\":\"src/main.ts\""));
+    }
 }
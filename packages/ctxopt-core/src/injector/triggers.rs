@@ -3,15 +3,28 @@
 //! Détermine quand et quoi injecter dans le stdin basé sur
 //! le ContentType détecté par le StreamAnalyzer.
 
-use super::templates::{Suggestion, SuggestionType};
+use super::config::{InjectorConfig, Level};
+use super::events::{EventSink, InjectionEvent, NoopSink, SkipReason};
+use super::profiler::{ProfileSummary, SelfProfiler};
+use super::templates::{Applicability, Suggestion, SuggestionType};
 use crate::stream::patterns::ContentType;
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
 /// Intervalle minimum entre deux injections (en secondes)
 const MIN_INJECTION_INTERVAL_SECS: u64 = 5;
 
-/// Nombre maximum de rappels prompt par session
-const MAX_PROMPT_REMINDERS: usize = 3;
+/// Compteurs d'injection agrégés par type de contenu (clé: `ContentType::kind()`)
+///
+/// Alimente `ContextInjector::kind_stats`, combiné côté `CtxOptSession::stats_breakdown`
+/// aux `occurrences`/`tokens` de `StreamAnalyzer::content_type_stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KindCounters {
+    /// Nombre de suggestions effectivement émises pour ce type
+    pub emitted: usize,
+    /// Nombre de candidates supprimées par le throttle pour ce type
+    pub throttled: usize,
+}
 
 /// Contexte d'injection avec état
 pub struct ContextInjector {
@@ -32,6 +45,22 @@ pub struct ContextInjector {
 
     /// Historique des types injectés (pour éviter répétitions)
     recent_types: Vec<SuggestionType>,
+
+    /// Destination des évènements d'injection (JSON, no-op, ...)
+    sink: Box<dyn EventSink>,
+
+    /// Confiance minimale requise pour injecter (mode "high-confidence only")
+    min_applicability: Applicability,
+
+    /// Configuration lint-style par type de suggestion (niveau, seuil, plafond)
+    config: InjectorConfig,
+
+    /// Métriques de détection-à-injection (comptes, suppressions, tokens économisés)
+    profiler: SelfProfiler,
+
+    /// Compteurs émis/throttled par type de contenu (clé: `ContentType::kind()`),
+    /// pour `CtxOptSession::stats_breakdown`
+    kind_stats: HashMap<String, KindCounters>,
 }
 
 impl ContextInjector {
@@ -45,6 +74,12 @@ impl ContextInjector {
             prompt_reminder_count: 0,
             enabled: true,
             recent_types: Vec::new(),
+            sink: Box::new(NoopSink),
+            // Par défaut, aucun filtrage: toute suggestion actionnable passe.
+            min_applicability: Applicability::Unspecified,
+            config: InjectorConfig::default(),
+            profiler: SelfProfiler::new(),
+            kind_stats: HashMap::new(),
         }
     }
 
@@ -56,6 +91,48 @@ impl ContextInjector {
         injector
     }
 
+    /// Remplace le sink d'évènements (par défaut: `NoopSink`)
+    #[allow(dead_code)]
+    pub fn with_sink(mut self, sink: Box<dyn EventSink>) -> Self {
+        self.sink = sink;
+        self
+    }
+
+    /// Accès au sink concret, pour inspection (ex: assertions sur `JsonSink` en test)
+    #[allow(dead_code)]
+    pub fn sink(&self) -> &dyn EventSink {
+        self.sink.as_ref()
+    }
+
+    /// Fixe la confiance minimale requise pour injecter une suggestion
+    ///
+    /// En mode "high-confidence only", les suggestions dont l'`Applicability`
+    /// est en-dessous de ce seuil sont silencieusement écartées.
+    #[allow(dead_code)]
+    pub fn with_min_applicability(mut self, min_applicability: Applicability) -> Self {
+        self.min_applicability = min_applicability;
+        self
+    }
+
+    /// Remplace la configuration lint-style par type de suggestion
+    #[allow(dead_code)]
+    pub fn with_config(mut self, config: InjectorConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Accès mutable à la configuration, pour l'ajuster après construction
+    /// (ex: depuis un fichier TOML déserialisé, ou `CtxOptSession::set_suggestion_level`)
+    pub fn config_mut(&mut self) -> &mut InjectorConfig {
+        &mut self.config
+    }
+
+    /// Résumé agrégé des métriques de détection-à-injection, dumpable en fin de session
+    #[allow(dead_code)]
+    pub fn profile_summary(&self) -> ProfileSummary {
+        self.profiler.summary()
+    }
+
     /// Active/désactive les suggestions
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
@@ -67,9 +144,9 @@ impl ContextInjector {
         self.enabled
     }
 
-    /// Vérifie si une injection est autorisée (throttling)
+    /// Vérifie si le throttle minimum entre deux injections est écoulé
     fn can_inject(&self) -> bool {
-        self.enabled && self.last_injection.elapsed() >= self.min_interval
+        self.last_injection.elapsed() >= self.min_interval
     }
 
     /// Vérifie si ce type a été récemment suggéré (3 derniers)
@@ -81,37 +158,126 @@ impl ContextInjector {
             .any(|t| t == suggestion_type)
     }
 
+    /// Nombre de suggestions déjà émises pour le `ContentType` donné, pour
+    /// appliquer `SuggestionConfig::max_per_session` (voir `classify`).
+    /// Repose sur `kind_stats`, déjà tenu à jour par `generate_suggestion`,
+    /// plutôt que d'introduire un compteur dédié par type.
+    fn emitted_count(&self, content_type: &ContentType) -> usize {
+        self.kind_stats.get(&content_type.kind()).map_or(0, |c| c.emitted)
+    }
+
     /// Évalue si une injection doit être faite pour le ContentType donné
     pub fn should_inject(&self, content_type: &ContentType) -> bool {
-        if !self.can_inject() {
-            return false;
+        self.classify(content_type).is_ok()
+    }
+
+    /// Logique pure de décision, partagée par `should_inject` et par le
+    /// chemin instrumenté de `generate_suggestion` (qui a besoin de la
+    /// raison précise pour le sink d'évènements).
+    ///
+    /// Les niveaux `Deny` du `InjectorConfig` bypassent le throttle et
+    /// l'anti-répétition pour ne jamais manquer une escalade explicite.
+    fn classify(&self, content_type: &ContentType) -> Result<(), SkipReason> {
+        if !self.enabled {
+            return Err(SkipReason::Disabled);
+        }
+
+        let suggestion_type = match content_type {
+            ContentType::BuildError { .. } => Some(SuggestionType::BuildErrors),
+            ContentType::LargeOutput { .. } => Some(SuggestionType::LargeOutput),
+            ContentType::FileRead { .. } => Some(SuggestionType::FileRead),
+            ContentType::PromptReady => Some(SuggestionType::PromptReminder),
+            // Les détecteurs personnalisés (chunk2-3) ne produisent pour
+            // l'instant que de la détection, pas de suggestion injectée.
+            ContentType::Custom { .. } | ContentType::Normal => None,
+        };
+        let config = suggestion_type.as_ref().map(|t| self.config.for_type(t));
+
+        if let Some(config) = config {
+            if config.level == Level::Allow {
+                return Err(SkipReason::ConfigSuppressed);
+            }
+        }
+        let deny = config.is_some_and(|c| c.level == Level::Deny);
+
+        if !deny && !self.can_inject() {
+            return Err(SkipReason::Throttled);
         }
 
         match content_type {
-            ContentType::BuildError { error_count, .. } => {
-                // Injecter si plus de 3 erreurs et pas récemment suggéré
-                *error_count >= 3 && !self.was_recently_suggested(&SuggestionType::BuildErrors)
+            ContentType::BuildError { error_count, tool } => {
+                let threshold = self.config.build_errors.threshold;
+                let max = self.config.build_errors.max_per_session;
+                if *error_count < threshold {
+                    Err(SkipReason::BelowThreshold)
+                } else if !deny && max.is_some_and(|max| self.emitted_count(content_type) >= max) {
+                    Err(SkipReason::BelowThreshold)
+                } else if !deny && self.was_recently_suggested(&SuggestionType::BuildErrors) {
+                    Err(SkipReason::RecentlySuggested)
+                } else if !deny
+                    && Applicability::for_build_error(*error_count, *tool) < self.min_applicability
+                {
+                    Err(SkipReason::BelowConfidence)
+                } else {
+                    Ok(())
+                }
             }
             ContentType::LargeOutput { size } => {
-                // Injecter si > 10KB et pas récemment suggéré
-                *size > 10000 && !self.was_recently_suggested(&SuggestionType::LargeOutput)
+                let threshold = self.config.large_output.threshold;
+                let max = self.config.large_output.max_per_session;
+                if *size <= threshold {
+                    Err(SkipReason::BelowThreshold)
+                } else if !deny && max.is_some_and(|max| self.emitted_count(content_type) >= max) {
+                    Err(SkipReason::BelowThreshold)
+                } else if !deny && self.was_recently_suggested(&SuggestionType::LargeOutput) {
+                    Err(SkipReason::RecentlySuggested)
+                } else if !deny && Applicability::Unspecified < self.min_applicability {
+                    // `large_output` n'est jamais qu'un nudge générique (Unspecified)
+                    Err(SkipReason::BelowConfidence)
+                } else {
+                    Ok(())
+                }
             }
             ContentType::FileRead { file_path } => {
-                // Injecter seulement si c'est un fichier code et pas récemment suggéré
-                Self::is_code_file(file_path)
-                    && !self.was_recently_suggested(&SuggestionType::FileRead)
+                let max = self.config.file_read.max_per_session;
+                if !Self::is_code_file(file_path) {
+                    Err(SkipReason::BelowThreshold)
+                } else if !deny && max.is_some_and(|max| self.emitted_count(content_type) >= max) {
+                    Err(SkipReason::BelowThreshold)
+                } else if !deny && self.was_recently_suggested(&SuggestionType::FileRead) {
+                    Err(SkipReason::RecentlySuggested)
+                } else if !deny && Applicability::HasPlaceholders < self.min_applicability {
+                    Err(SkipReason::BelowConfidence)
+                } else {
+                    Ok(())
+                }
             }
             ContentType::PromptReady => {
-                // Limiter les rappels prompt à MAX_PROMPT_REMINDERS par session
-                self.prompt_reminder_count < MAX_PROMPT_REMINDERS
+                let max = self.config.prompt_reminder.max_per_session;
+                if !deny && max.is_some_and(|max| self.prompt_reminder_count >= max) {
+                    Err(SkipReason::BelowThreshold)
+                } else if !deny && Applicability::Unspecified < self.min_applicability {
+                    Err(SkipReason::BelowConfidence)
+                } else {
+                    Ok(())
+                }
             }
-            ContentType::Normal => false,
+            ContentType::Custom { .. } | ContentType::Normal => Err(SkipReason::BelowThreshold),
         }
     }
 
     /// Génère une suggestion pour le ContentType donné
     pub fn generate_suggestion(&mut self, content_type: &ContentType) -> Option<Suggestion> {
-        if !self.should_inject(content_type) {
+        if let Err(reason) = self.classify(content_type) {
+            self.profiler.record_suppressed(&reason);
+            if reason == SkipReason::Throttled {
+                self.kind_stats.entry(content_type.kind()).or_default().throttled += 1;
+            }
+            self.sink.record(&InjectionEvent::Skipped {
+                content_type,
+                reason,
+                suggestions_count: self.suggestions_count,
+            });
             return None;
         }
 
@@ -132,18 +298,28 @@ impl ContextInjector {
                 self.prompt_reminder_count += 1;
                 Some(Suggestion::prompt_reminder())
             }
-            ContentType::Normal => None,
+            ContentType::Custom { .. } | ContentType::Normal => None,
         };
 
         if let Some(ref s) = suggestion {
+            let gap = self.last_injection.elapsed();
             self.last_injection = Instant::now();
             self.suggestions_count += 1;
+            self.kind_stats.entry(content_type.kind()).or_default().emitted += 1;
             self.recent_types.push(s.suggestion_type.clone());
 
             // Garder seulement les 10 derniers types
             if self.recent_types.len() > 10 {
                 self.recent_types.remove(0);
             }
+
+            self.profiler.record_emitted(&s.suggestion_type, gap);
+
+            self.sink.record(&InjectionEvent::Emitted {
+                content_type,
+                suggestion_type: &s.suggestion_type,
+                suggestions_count: self.suggestions_count,
+            });
         }
 
         suggestion
@@ -175,6 +351,13 @@ impl ContextInjector {
         self.prompt_reminder_count = 0;
         self.recent_types.clear();
         self.last_injection = Instant::now() - Duration::from_secs(60);
+        self.kind_stats.clear();
+    }
+
+    /// Compteurs émis/throttled par type de contenu détecté, combinés côté
+    /// `CtxOptSession::stats_breakdown` aux `occurrences`/`tokens` du `StreamAnalyzer`
+    pub fn kind_stats(&self) -> &HashMap<String, KindCounters> {
+        &self.kind_stats
     }
 
     /// Retourne le temps restant avant prochaine injection possible (en ms, utilisé dans les tests)
@@ -198,6 +381,7 @@ impl Default for ContextInjector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::events::JsonSink;
     use crate::stream::patterns::BuildTool;
 
     #[test]
@@ -320,6 +504,74 @@ mod tests {
         assert_eq!(injector.prompt_reminders_used(), 3);
     }
 
+    #[test]
+    fn test_max_per_session_caps_large_output() {
+        let mut injector = ContextInjector::with_interval(10);
+        injector
+            .config_mut()
+            .set_max_per_session(&SuggestionType::LargeOutput, Some(2));
+
+        let large = ContentType::LargeOutput { size: 20000 };
+        // Types différents de LargeOutput, intercalés pour ne pas se faire
+        // bloquer par `was_recently_suggested` (3 derniers types) entre deux
+        // émissions de LargeOutput.
+        let fillers = [
+            ContentType::FileRead {
+                file_path: "a.rs".to_string(),
+            },
+            ContentType::PromptReady,
+            ContentType::BuildError {
+                error_count: 10,
+                tool: BuildTool::TypeScript,
+            },
+        ];
+
+        injector.last_injection = Instant::now() - Duration::from_secs(60);
+        assert!(
+            injector.generate_suggestion(&large).is_some(),
+            "1st large output should be allowed"
+        );
+
+        for filler in &fillers {
+            injector.last_injection = Instant::now() - Duration::from_secs(60);
+            injector.generate_suggestion(filler);
+        }
+        injector.last_injection = Instant::now() - Duration::from_secs(60);
+        assert!(
+            injector.generate_suggestion(&large).is_some(),
+            "2nd large output should be allowed (cap is 2)"
+        );
+
+        for filler in &fillers {
+            injector.last_injection = Instant::now() - Duration::from_secs(60);
+            injector.generate_suggestion(filler);
+        }
+        injector.last_injection = Instant::now() - Duration::from_secs(60);
+        assert!(
+            injector.generate_suggestion(&large).is_none(),
+            "3rd large output should be blocked by the per-session cap"
+        );
+    }
+
+    #[test]
+    fn test_max_per_session_does_not_affect_other_types() {
+        let mut injector = ContextInjector::with_interval(10);
+        injector
+            .config_mut()
+            .set_max_per_session(&SuggestionType::LargeOutput, Some(1));
+
+        injector.generate_suggestion(&ContentType::LargeOutput { size: 20000 });
+        injector.last_injection = Instant::now() - Duration::from_secs(60);
+
+        // LargeOutput a atteint son plafond, mais BuildErrors n'a pas de plafond configuré
+        assert!(injector
+            .generate_suggestion(&ContentType::BuildError {
+                error_count: 10,
+                tool: BuildTool::TypeScript,
+            })
+            .is_some());
+    }
+
     #[test]
     fn test_set_enabled() {
         let mut injector = ContextInjector::new();
@@ -373,4 +625,233 @@ mod tests {
         let injector = ContextInjector::new();
         assert!(!injector.should_inject(&ContentType::Normal));
     }
+
+    fn json_lines(injector: &ContextInjector) -> Vec<String> {
+        injector
+            .sink()
+            .as_any()
+            .downcast_ref::<JsonSink>()
+            .expect("sink should be a JsonSink")
+            .lines()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_sink_records_emitted_event() {
+        let mut injector = ContextInjector::with_interval(10).with_sink(Box::new(JsonSink::new()));
+
+        let suggestion = injector.generate_suggestion(&ContentType::BuildError {
+            error_count: 10,
+            tool: BuildTool::Rust,
+        });
+        assert!(suggestion.is_some());
+
+        let lines = json_lines(&injector);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("\"skipped\":false"));
+        assert!(lines[0].contains("\"suggestion_type\":\"build_errors\""));
+        assert!(lines[0].contains("\"suggestions_count\":1"));
+    }
+
+    #[test]
+    fn test_sink_records_throttled_skip() {
+        let mut injector = ContextInjector::with_interval(10_000).with_sink(Box::new(JsonSink::new()));
+        injector.last_injection = Instant::now() - Duration::from_secs(60);
+
+        let content = ContentType::BuildError {
+            error_count: 10,
+            tool: BuildTool::Rust,
+        };
+        assert!(injector.generate_suggestion(&content).is_some());
+        assert!(injector.generate_suggestion(&content).is_none());
+
+        let lines = json_lines(&injector);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains("\"skip_reason\":\"throttled\""));
+    }
+
+    #[test]
+    fn test_sink_records_below_threshold_skip() {
+        let mut injector = ContextInjector::with_interval(10).with_sink(Box::new(JsonSink::new()));
+
+        let suggestion = injector.generate_suggestion(&ContentType::BuildError {
+            error_count: 1,
+            tool: BuildTool::Rust,
+        });
+        assert!(suggestion.is_none());
+
+        let lines = json_lines(&injector);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("\"skip_reason\":\"below_threshold\""));
+    }
+
+    #[test]
+    fn test_sink_records_disabled_skip() {
+        let mut injector = ContextInjector::with_interval(10).with_sink(Box::new(JsonSink::new()));
+        injector.set_enabled(false);
+
+        let suggestion = injector.generate_suggestion(&ContentType::PromptReady);
+        assert!(suggestion.is_none());
+
+        let lines = json_lines(&injector);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("\"skip_reason\":\"disabled\""));
+    }
+
+    #[test]
+    fn test_min_applicability_suppresses_low_confidence_build_errors() {
+        let injector = ContextInjector::new().with_min_applicability(Applicability::MachineApplicable);
+
+        // Peu d'erreurs ou outil générique: juste HasPlaceholders/MaybeIncorrect, filtré.
+        assert!(!injector.should_inject(&ContentType::BuildError {
+            error_count: 3,
+            tool: BuildTool::TypeScript,
+        }));
+
+        // Beaucoup d'erreurs sur un outil connu: MachineApplicable, passe.
+        assert!(injector.should_inject(&ContentType::BuildError {
+            error_count: 20,
+            tool: BuildTool::Rust,
+        }));
+    }
+
+    #[test]
+    fn test_min_applicability_suppresses_large_output_by_default_confidence() {
+        let injector = ContextInjector::new().with_min_applicability(Applicability::HasPlaceholders);
+
+        // LargeOutput est toujours Unspecified, donc filtré dès que le seuil monte.
+        assert!(!injector.should_inject(&ContentType::LargeOutput { size: 50000 }));
+    }
+
+    #[test]
+    fn test_min_applicability_default_allows_everything() {
+        let injector = ContextInjector::new();
+        assert!(injector.should_inject(&ContentType::LargeOutput { size: 50000 }));
+    }
+
+    #[test]
+    fn test_sink_records_below_confidence_skip() {
+        let mut injector = ContextInjector::with_interval(10)
+            .with_sink(Box::new(JsonSink::new()))
+            .with_min_applicability(Applicability::MachineApplicable);
+
+        let suggestion = injector.generate_suggestion(&ContentType::LargeOutput { size: 50000 });
+        assert!(suggestion.is_none());
+
+        let lines = json_lines(&injector);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("\"skip_reason\":\"below_confidence\""));
+    }
+
+    #[test]
+    fn test_config_allow_suppresses_suggestion_type() {
+        let mut config = InjectorConfig::default();
+        config.set_level(&SuggestionType::LargeOutput, Level::Allow);
+        let injector = ContextInjector::new().with_config(config);
+
+        assert!(!injector.should_inject(&ContentType::LargeOutput { size: 50_000 }));
+    }
+
+    #[test]
+    fn test_config_deny_bypasses_throttle_and_recent_types() {
+        let mut config = InjectorConfig::default();
+        config.set_level(&SuggestionType::BuildErrors, Level::Deny);
+        let mut injector = ContextInjector::with_interval(60_000).with_config(config);
+
+        let content = ContentType::BuildError {
+            error_count: 10,
+            tool: BuildTool::Rust,
+        };
+
+        // Sans Deny ceci serait bloqué par le throttle de 60s; Deny l'escalade.
+        assert!(injector.generate_suggestion(&content).is_some());
+        assert!(injector.generate_suggestion(&content).is_some());
+    }
+
+    #[test]
+    fn test_config_per_type_threshold_override() {
+        let mut config = InjectorConfig::default();
+        config.set_threshold(&SuggestionType::BuildErrors, 1);
+        let injector = ContextInjector::new().with_config(config);
+
+        assert!(injector.should_inject(&ContentType::BuildError {
+            error_count: 1,
+            tool: BuildTool::Rust,
+        }));
+
+        // Le seuil de LargeOutput n'est pas affecté
+        assert!(!injector.should_inject(&ContentType::LargeOutput { size: 1000 }));
+    }
+
+    #[test]
+    fn test_config_allow_precedence_over_deny_elsewhere() {
+        let mut config = InjectorConfig::default();
+        config.set_level(&SuggestionType::FileRead, Level::Allow);
+        config.set_level(&SuggestionType::BuildErrors, Level::Deny);
+        let injector = ContextInjector::new().with_config(config);
+
+        assert!(!injector.should_inject(&ContentType::FileRead {
+            file_path: "src/main.rs".to_string(),
+        }));
+        assert!(injector.should_inject(&ContentType::BuildError {
+            error_count: 3,
+            tool: BuildTool::Rust,
+        }));
+    }
+
+    #[test]
+    fn test_profile_summary_tracks_emitted_and_suppressed() {
+        let mut injector = ContextInjector::with_interval(10);
+
+        assert!(injector.generate_suggestion(&ContentType::BuildError {
+            error_count: 10,
+            tool: BuildTool::Rust,
+        }).is_some());
+
+        // Immédiatement: bloqué par throttle.
+        assert!(injector.generate_suggestion(&ContentType::BuildError {
+            error_count: 10,
+            tool: BuildTool::Rust,
+        }).is_none());
+
+        let summary = injector.profile_summary();
+        assert_eq!(summary.build_errors_injections, 1);
+        assert_eq!(summary.suppressed_throttled, 1);
+        assert!(summary.estimated_tokens_saved > 0);
+    }
+
+    #[test]
+    fn test_kind_stats_tracks_emitted_and_throttled_per_content_type() {
+        let mut injector = ContextInjector::with_interval(10_000);
+
+        let content = ContentType::BuildError {
+            error_count: 10,
+            tool: BuildTool::Rust,
+        };
+
+        assert!(injector.generate_suggestion(&content).is_some());
+        // Immédiatement: bloqué par throttle, compté sur le même bucket.
+        assert!(injector.generate_suggestion(&content).is_none());
+
+        let stats = injector.kind_stats();
+        let build_errors = stats.get("build_error").expect("build_error bucket");
+        assert_eq!(build_errors.emitted, 1);
+        assert_eq!(build_errors.throttled, 1);
+    }
+
+    #[test]
+    fn test_kind_stats_cleared_on_reset() {
+        let mut injector = ContextInjector::with_interval(10);
+        injector.generate_suggestion(&ContentType::PromptReady);
+        injector.reset();
+
+        assert!(injector.kind_stats().is_empty());
+    }
+
+    #[test]
+    fn test_default_sink_is_noop() {
+        // Sans sink explicite, le type concret doit rester `NoopSink`
+        let injector = ContextInjector::new();
+        assert!(injector.sink().as_any().downcast_ref::<NoopSink>().is_some());
+    }
 }
@@ -0,0 +1,316 @@
+//! Contrôle de la verbosité des suggestions affichées
+//!
+//! Reprend le split pretty/terse/quiet des formatteurs de `libtest`:
+//! `ContextInjector` (voir `triggers`) décide QUOI générer (lint-style,
+//! seuils, throttle), `SuggestionRenderer` décide COMMENT l'afficher
+//! (capacité ANSI); ce module décide COMBIEN en montrer, pour les sessions
+//! longues où le rappel `prompt_reminder` et les nudges `large_output`
+//! répétés finissent par noyer les suggestions à fort signal.
+
+use super::templates::{Suggestion, SuggestionType};
+use std::time::{Duration, Instant};
+
+/// Fenêtre de coalescence par défaut en mode `Terse`: les suggestions
+/// répétées d'un même type dans cette fenêtre sont fusionnées en une seule
+/// ligne comptée plutôt que répétées une à une.
+const DEFAULT_COLLAPSE_WINDOW: Duration = Duration::from_secs(30);
+
+/// Verbosité des suggestions affichées, une entrée par mode de `libtest`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionVerbosity {
+    /// Seules les suggestions `BuildErrors` (à fort signal) sont affichées;
+    /// tout le reste (y compris `PromptReminder`) est entièrement supprimé
+    Quiet,
+    /// Les répétitions d'un même type dans la fenêtre de coalescence sont
+    /// fusionnées en une ligne comptée (ex: `"3x large output, ~840KB
+    /// total"`); `BuildErrors` reste affiché en entier, jamais coalescé
+    Terse,
+    /// Comportement historique: un message détaillé par suggestion
+    Pretty,
+}
+
+impl SuggestionVerbosity {
+    /// Parse un mode depuis son libellé (`"quiet"`, `"terse"`, `"pretty"`),
+    /// insensible à la casse. Utilisé par `CtxOptSession::set_verbosity`
+    /// pour accepter le mode passé depuis Node.js.
+    pub(crate) fn parse(mode: &str) -> Option<Self> {
+        match mode.to_ascii_lowercase().as_str() {
+            "quiet" => Some(Self::Quiet),
+            "terse" => Some(Self::Terse),
+            "pretty" => Some(Self::Pretty),
+            _ => None,
+        }
+    }
+}
+
+impl Default for SuggestionVerbosity {
+    fn default() -> Self {
+        Self::Pretty
+    }
+}
+
+/// Groupe en cours de coalescence pour un `SuggestionType` en mode `Terse`
+#[derive(Debug, Clone, Copy)]
+struct PendingGroup {
+    count: usize,
+    total_size_bytes: usize,
+    window_start: Instant,
+}
+
+impl PendingGroup {
+    fn start() -> Self {
+        Self {
+            count: 0,
+            total_size_bytes: 0,
+            window_start: Instant::now(),
+        }
+    }
+
+    /// Ligne comptée façon `"3x large output, ~840KB total"`; le `~...KB
+    /// total` n'apparaît que si une taille a été accumulée (seul
+    /// `LargeOutput` en porte une pour l'instant).
+    fn format(&self, suggestion_type: &SuggestionType) -> String {
+        let label = match suggestion_type {
+            // BuildErrors ne passe jamais par ici, voir `VerbosityFilter::filter`
+            SuggestionType::BuildErrors | SuggestionType::LargeOutput => "large output",
+            SuggestionType::FileRead => "file reads",
+            SuggestionType::PromptReminder => "prompt reminders",
+        };
+
+        if self.total_size_bytes > 0 {
+            format!(
+                "\x1b[90m[ctxopt] {}x {}, ~{}KB total\x1b[0m\n",
+                self.count,
+                label,
+                self.total_size_bytes / 1024
+            )
+        } else {
+            format!("\x1b[90m[ctxopt] {}x {}\x1b[0m\n", self.count, label)
+        }
+    }
+}
+
+/// Filtre le volume de suggestions affichées selon la [`SuggestionVerbosity`]
+/// configurée, avec l'état nécessaire pour coalescer les répétitions en
+/// mode `Terse`
+///
+/// Un champ par `SuggestionType` plutôt qu'une `HashMap` (même choix que
+/// `profiler::TypeCounters`): l'ensemble des types est fixe et petit.
+/// `BuildErrors` n'a pas de slot: il n'est jamais coalescé.
+pub struct VerbosityFilter {
+    verbosity: SuggestionVerbosity,
+    collapse_window: Duration,
+    large_output: Option<PendingGroup>,
+    file_read: Option<PendingGroup>,
+    prompt_reminder: Option<PendingGroup>,
+}
+
+impl VerbosityFilter {
+    /// Crée un filtre avec la fenêtre de coalescence par défaut (30s)
+    pub fn new(verbosity: SuggestionVerbosity) -> Self {
+        Self {
+            verbosity,
+            collapse_window: DEFAULT_COLLAPSE_WINDOW,
+            large_output: None,
+            file_read: None,
+            prompt_reminder: None,
+        }
+    }
+
+    /// Crée un filtre avec une fenêtre de coalescence personnalisée (utilisé dans les tests)
+    #[allow(dead_code)]
+    pub fn with_collapse_window(verbosity: SuggestionVerbosity, collapse_window: Duration) -> Self {
+        Self {
+            collapse_window,
+            ..Self::new(verbosity)
+        }
+    }
+
+    /// Change le mode de verbosité (utilisé par `CtxOptSession::set_verbosity`)
+    pub fn set_verbosity(&mut self, verbosity: SuggestionVerbosity) {
+        self.verbosity = verbosity;
+    }
+
+    fn slot_mut(&mut self, suggestion_type: &SuggestionType) -> Option<&mut Option<PendingGroup>> {
+        match suggestion_type {
+            SuggestionType::BuildErrors => None,
+            SuggestionType::LargeOutput => Some(&mut self.large_output),
+            SuggestionType::FileRead => Some(&mut self.file_read),
+            SuggestionType::PromptReminder => Some(&mut self.prompt_reminder),
+        }
+    }
+
+    /// Décide si/comment afficher `suggestion`, selon la verbosité
+    /// configurée. `size_bytes` est la taille brute associée, accumulée
+    /// dans la ligne comptée du mode `Terse` (seul `LargeOutput` en porte
+    /// une pour l'instant, `None` pour les autres types).
+    ///
+    /// `BuildErrors` n'est jamais coalescé ni supprimé: c'est la seule
+    /// suggestion à fort signal, affichée immédiatement quel que soit le mode.
+    pub fn filter(&mut self, suggestion: &Suggestion, size_bytes: Option<usize>) -> Option<String> {
+        if suggestion.suggestion_type == SuggestionType::BuildErrors {
+            return Some(suggestion.format_for_display());
+        }
+
+        match self.verbosity {
+            SuggestionVerbosity::Quiet => None,
+            SuggestionVerbosity::Pretty => Some(suggestion.format_for_display()),
+            SuggestionVerbosity::Terse => {
+                let suggestion_type = suggestion.suggestion_type.clone();
+                let collapse_window = self.collapse_window;
+                let slot = self
+                    .slot_mut(&suggestion_type)
+                    .expect("non-BuildErrors types always have a coalescing slot");
+
+                let due = slot.as_ref().is_some_and(|g| g.window_start.elapsed() >= collapse_window);
+                let flushed = if due {
+                    slot.take().map(|g| g.format(&suggestion_type))
+                } else {
+                    None
+                };
+
+                let group = slot.get_or_insert_with(PendingGroup::start);
+                group.count += 1;
+                group.total_size_bytes += size_bytes.unwrap_or(0);
+
+                flushed
+            }
+        }
+    }
+
+    /// Vide tous les groupes en cours de coalescence et les retourne formatés
+    ///
+    /// `filter` ne flush un groupe que quand une *nouvelle* suggestion du
+    /// même type arrive après la fenêtre de coalescence: le dernier groupe
+    /// en cours de chaque type resterait donc piégé pour toujours si rien
+    /// ne l'en fait sortir. À appeler en fin de session (pour ne pas perdre
+    /// les dernières suggestions accumulées) et en quittant le mode `Terse`
+    /// (pour ne pas garder un décompte qui traîne alors qu'il ne sera plus
+    /// jamais affiché).
+    pub fn flush(&mut self) -> Vec<String> {
+        [
+            (SuggestionType::LargeOutput, self.large_output.take()),
+            (SuggestionType::FileRead, self.file_read.take()),
+            (SuggestionType::PromptReminder, self.prompt_reminder.take()),
+        ]
+        .into_iter()
+        .filter_map(|(suggestion_type, group)| group.map(|g| g.format(&suggestion_type)))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::patterns::BuildTool;
+
+    #[test]
+    fn test_verbosity_parse_known_modes() {
+        assert_eq!(SuggestionVerbosity::parse("quiet"), Some(SuggestionVerbosity::Quiet));
+        assert_eq!(SuggestionVerbosity::parse("TERSE"), Some(SuggestionVerbosity::Terse));
+        assert_eq!(SuggestionVerbosity::parse("Pretty"), Some(SuggestionVerbosity::Pretty));
+        assert_eq!(SuggestionVerbosity::parse("loud"), None);
+    }
+
+    #[test]
+    fn test_pretty_passes_everything_through() {
+        let mut filter = VerbosityFilter::new(SuggestionVerbosity::Pretty);
+        let suggestion = Suggestion::prompt_reminder();
+        assert!(filter.filter(&suggestion, None).is_some());
+    }
+
+    #[test]
+    fn test_quiet_suppresses_prompt_reminder() {
+        let mut filter = VerbosityFilter::new(SuggestionVerbosity::Quiet);
+        let suggestion = Suggestion::prompt_reminder();
+        assert!(filter.filter(&suggestion, None).is_none());
+    }
+
+    #[test]
+    fn test_quiet_still_surfaces_build_errors() {
+        let mut filter = VerbosityFilter::new(SuggestionVerbosity::Quiet);
+        let suggestion = Suggestion::build_errors(10, BuildTool::Rust);
+        assert!(filter.filter(&suggestion, None).is_some());
+    }
+
+    #[test]
+    fn test_terse_never_coalesces_build_errors() {
+        let mut filter = VerbosityFilter::new(SuggestionVerbosity::Terse);
+        let suggestion = Suggestion::build_errors(10, BuildTool::Rust);
+
+        // Chaque occurrence s'affiche immédiatement, sans accumulation.
+        assert!(filter.filter(&suggestion, None).is_some());
+        assert!(filter.filter(&suggestion, None).is_some());
+    }
+
+    #[test]
+    fn test_terse_collapses_repeated_large_output_after_window() {
+        let mut filter =
+            VerbosityFilter::with_collapse_window(SuggestionVerbosity::Terse, Duration::from_millis(20));
+        let suggestion = Suggestion::large_output(10240);
+
+        // Les deux premières occurrences arrivent dans la fenêtre: accumulées, rien affiché.
+        assert!(filter.filter(&suggestion, Some(512_000)).is_none());
+        assert!(filter.filter(&suggestion, Some(358_400)).is_none());
+
+        std::thread::sleep(Duration::from_millis(30));
+
+        // La fenêtre est écoulée: la troisième occurrence déclenche le flush des 2 précédentes.
+        let flushed = filter.filter(&suggestion, Some(1024)).expect("window elapsed, should flush");
+        assert!(flushed.contains("2x large output"));
+        assert!(flushed.contains("850KB total"));
+    }
+
+    #[test]
+    fn test_terse_collapses_file_read_without_size() {
+        let mut filter =
+            VerbosityFilter::with_collapse_window(SuggestionVerbosity::Terse, Duration::from_millis(20));
+        let suggestion = Suggestion::file_read("src/main.ts");
+
+        assert!(filter.filter(&suggestion, None).is_none());
+        std::thread::sleep(Duration::from_millis(30));
+
+        let flushed = filter.filter(&suggestion, None).expect("window elapsed, should flush");
+        assert_eq!(flushed, "\x1b[90m[ctxopt] 1x file reads\x1b[0m\n");
+    }
+
+    #[test]
+    fn test_flush_emits_the_last_pending_group() {
+        let mut filter = VerbosityFilter::new(SuggestionVerbosity::Terse);
+        let suggestion = Suggestion::large_output(10240);
+
+        // Accumulées dans la fenêtre, rien n'est affiché tant que personne ne les flush.
+        assert!(filter.filter(&suggestion, Some(512_000)).is_none());
+        assert!(filter.filter(&suggestion, Some(358_400)).is_none());
+
+        let flushed = filter.flush();
+        assert_eq!(flushed.len(), 1);
+        assert!(flushed[0].contains("2x large output"));
+        assert!(flushed[0].contains("850KB total"));
+
+        // L'état a été drainé: un flush répété ne renvoie plus rien.
+        assert!(filter.flush().is_empty());
+    }
+
+    #[test]
+    fn test_flush_drains_every_pending_type_independently() {
+        let mut filter = VerbosityFilter::new(SuggestionVerbosity::Terse);
+        filter.filter(&Suggestion::large_output(1024), Some(1024)).map(|_| ());
+        filter.filter(&Suggestion::file_read("src/main.ts"), None).map(|_| ());
+        filter.filter(&Suggestion::prompt_reminder(), None).map(|_| ());
+
+        let flushed = filter.flush();
+        assert_eq!(flushed.len(), 3);
+        assert!(filter.flush().is_empty());
+    }
+
+    #[test]
+    fn test_set_verbosity_switches_mode() {
+        let mut filter = VerbosityFilter::new(SuggestionVerbosity::Quiet);
+        let suggestion = Suggestion::prompt_reminder();
+        assert!(filter.filter(&suggestion, None).is_none());
+
+        filter.set_verbosity(SuggestionVerbosity::Pretty);
+        assert!(filter.filter(&suggestion, None).is_some());
+    }
+}
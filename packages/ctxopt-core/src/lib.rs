@@ -46,6 +46,12 @@ mod tests;
 // NAPI bindings - only when not benchmarking
 // ============================================================================
 
+#[cfg(not(feature = "bench"))]
+mod events;
+#[cfg(not(feature = "bench"))]
+mod pool;
+#[cfg(not(feature = "bench"))]
+mod profiling;
 #[cfg(not(feature = "bench"))]
 mod pty;
 
@@ -53,13 +59,29 @@ mod pty;
 mod napi_bindings {
     use napi::bindgen_prelude::*;
     use napi_derive::napi;
+    use std::collections::HashSet;
+    use std::path::PathBuf;
     use std::sync::Arc;
-    use tokio::sync::{Mutex, RwLock};
+    use std::time::Duration;
+    use tokio::sync::{mpsc, Mutex, RwLock};
 
     use crate::config::Config;
-    use crate::injector::ContextInjector;
-    use crate::pty::{PtyManager, PtySize};
-    use crate::stream::StreamAnalyzer;
+    use crate::events::SessionEvent;
+    use crate::injector::templates::{self, Suggestion, SuggestionType};
+    use crate::injector::{
+        AnalysisReport, ContextInjector, Level, SuggestionReport, SuggestionVerbosity,
+        VerbosityFilter,
+    };
+    use crate::profiling::SessionProfiler;
+    use crate::pty::{
+        query_controlling_terminal_size, spawn_watcher, FilterChain, PtyManager, PtySize,
+        WatchConfig, WatchHandle,
+    };
+    use crate::stream::{ContentType, SharedAnalyzer};
+    use crate::tokens::TokenEstimator;
+
+    /// Nombre de lignes de contexte citées par `read(render_mode: "annotated")`
+    const ANNOTATED_CONTEXT_LINES: usize = 3;
 
     /// Version du module natif
     #[napi]
@@ -73,6 +95,21 @@ mod napi_bindings {
         "pong".to_string()
     }
 
+    /// Suggestion sérialisée pour Node.js
+    ///
+    /// `code` est un identifiant stable (ex: `D0001`), greppable et
+    /// indépendant du libellé affiché; `utils::explain` le résout vers une
+    /// explication longue et la commande d'optimisation recommandée.
+    #[napi(object)]
+    #[derive(Clone)]
+    pub struct SuggestionInfo {
+        /// Code stable de la suggestion, voir `utils::explain`
+        pub code: String,
+
+        /// Message formaté pour affichage terminal
+        pub message: String,
+    }
+
     /// Résultat d'une lecture du PTY
     #[napi(object)]
     #[derive(Clone)]
@@ -84,7 +121,7 @@ mod napi_bindings {
         pub clean_output: String,
 
         /// Suggestions générées (si applicable)
-        pub suggestions: Vec<String>,
+        pub suggestions: Vec<SuggestionInfo>,
 
         /// Estimation de tokens pour cet output
         pub token_estimate: u32,
@@ -94,6 +131,12 @@ mod napi_bindings {
 
         /// Taille totale accumulée dans le buffer
         pub total_size: u32,
+
+        /// Nombre de lignes quasi-identiques fusionnées dans `clean_output`
+        pub lines_saved: u32,
+
+        /// Tokens économisés par la fusion des quasi-doublons
+        pub tokens_saved: u32,
     }
 
     /// Statistiques de session
@@ -113,23 +156,145 @@ mod napi_bindings {
         pub elapsed_ms: u32,
     }
 
+    /// Ligne de statistiques pour un type de contenu détecté, exposée côté
+    /// Node.js par [`CtxOptSession::stats_breakdown`]
+    ///
+    /// Suit le modèle d'agrégation par bin du `CodeStats` de rustc: une ligne
+    /// par discriminant de `ContentType` (voir `ContentType::kind`), pour
+    /// qu'un dashboard puisse montrer par exemple que 80% du gaspillage
+    /// estimé vient des dumps `large_output` plutôt que des `file_read`.
+    #[napi(object)]
+    #[derive(Clone)]
+    pub struct ContentTypeStat {
+        /// Discriminant du type de contenu (voir `ContentType::kind`), ex:
+        /// `"build_error"`, `"large_output"`, `"custom:maven"`
+        pub kind: String,
+
+        /// Nombre de détections de ce type depuis le dernier `reset_stats`
+        pub occurrences: u32,
+
+        /// Somme des `token_estimate` des chunks où ce type est apparu
+        pub tokens: u32,
+
+        /// Nombre de suggestions effectivement émises pour ce type
+        pub suggestions_emitted: u32,
+
+        /// Nombre de suggestions supprimées par le throttle pour ce type
+        pub suggestions_throttled: u32,
+
+        /// Estimation des tokens économisés par les suggestions émises pour
+        /// ce type (voir `templates::estimated_tokens_saved`)
+        pub estimated_tokens_saved: u32,
+    }
+
+    /// Résumé figé d'une distribution robuste (voir `stream::stats::Distribution`),
+    /// exposé côté Node.js par [`CtxOptSession::session_summary`]
+    #[napi(object)]
+    #[derive(Clone)]
+    pub struct DistributionStat {
+        pub mean: f64,
+        pub median: f64,
+        pub q1: f64,
+        pub q3: f64,
+        pub std_dev: f64,
+        pub winsorized_mean: f64,
+    }
+
+    impl From<crate::stream::DistributionSummary> for DistributionStat {
+        fn from(summary: crate::stream::DistributionSummary) -> Self {
+            Self {
+                mean: summary.mean,
+                median: summary.median,
+                q1: summary.q1,
+                q3: summary.q3,
+                std_dev: summary.std_dev,
+                winsorized_mean: summary.winsorized_mean,
+            }
+        }
+    }
+
+    /// Ligne clé/valeur, pour exposer une `HashMap` côté Node.js (les objets
+    /// NAPI n'ont pas d'équivalent map natif; même aplatissement que
+    /// [`ContentTypeStat`])
+    #[napi(object)]
+    #[derive(Clone)]
+    pub struct CountRow {
+        pub key: String,
+        pub count: u32,
+    }
+
+    /// Résumé statistique de toute la session, exposé côté Node.js par
+    /// [`CtxOptSession::session_summary`]
+    ///
+    /// Complète [`SessionStats`] (compteurs bruts) par des distributions
+    /// robustes aux outliers (voir `stream::stats::SessionStats`), pour un
+    /// dashboard qui veut distinguer un gros dump de log isolé de la taille
+    /// de chunk "typique" d'une session.
+    #[napi(object)]
+    #[derive(Clone)]
+    pub struct SessionStatsSummary {
+        pub total_bytes: u32,
+        pub chunk_count: u32,
+        pub chunk_size: DistributionStat,
+        pub token_estimate: DistributionStat,
+        pub tool_error_counts: Vec<CountRow>,
+        pub suggestion_counts: Vec<CountRow>,
+    }
+
+    /// Timing agrégé d'une phase de `read()`, exposé côté Node.js
+    #[napi(object)]
+    #[derive(Clone)]
+    pub struct PhaseTiming {
+        /// Nom stable de la phase (`"pty_read"`, `"analyze"`, `"inject"`)
+        pub phase: String,
+
+        /// Nombre d'appels accumulés
+        pub calls: u32,
+
+        /// Temps total écoulé en microsecondes
+        pub total_us: u32,
+
+        /// Temps moyen par appel en microsecondes
+        pub avg_us: u32,
+    }
+
     /// Session PTY principale exposée à Node.js
     #[napi]
     pub struct CtxOptSession {
         /// Gestionnaire PTY
         pty: Arc<Mutex<PtyManager>>,
 
-        /// Analyseur de flux (`RwLock` pour permettre reads concurrents sur stats)
-        analyzer: Arc<RwLock<StreamAnalyzer>>,
+        /// Analyseur de flux, déjà protégé en interne par un `RwLock` (voir
+        /// `stream::shared::SharedAnalyzer`) pour permettre des reads
+        /// concurrents sur les stats pendant qu'un chunk est analysé
+        analyzer: Arc<SharedAnalyzer>,
 
         /// Injecteur de contexte (`RwLock` pour permettre reads concurrents sur stats)
         injector: Arc<RwLock<ContextInjector>>,
 
+        /// Chaîne de filtres appliquée à chaque écriture vers le PTY
+        /// (macros de raccourcis, garde-fous de paste, auto-réponses, ...)
+        filters: Arc<Mutex<FilterChain>>,
+
+        /// Auto-profiling des phases de `read()`, déjà protégé en interne
+        /// (voir `profiling::SessionProfiler`); no-op tant que la collecte
+        /// n'est pas activée à la construction
+        profiler: Arc<SessionProfiler>,
+
         /// Configuration
         config: Config,
 
         /// Timestamp de démarrage
         started_at: std::time::Instant,
+
+        /// Surveillance de fichiers active (mode watch, voir
+        /// [`Self::watch`]/[`Self::poll_watch`]); `None` tant que `watch` n'a
+        /// pas été appelé
+        watch: Arc<Mutex<Option<(WatchHandle, mpsc::Receiver<usize>)>>>,
+
+        /// Filtre de verbosité des suggestions affichées par [`Self::read`]
+        /// (voir `injector::verbosity`); n'affecte pas `read_json`/`read_report`
+        verbosity: Arc<Mutex<VerbosityFilter>>,
     }
 
     #[napi]
@@ -137,15 +302,19 @@ mod napi_bindings {
         /// Crée une nouvelle session PTY pour Claude Code
         ///
         /// # Arguments
-        /// * `rows` - Nombre de lignes du terminal (défaut: 24)
-        /// * `cols` - Nombre de colonnes du terminal (défaut: 80)
+        /// * `rows` - Nombre de lignes du terminal (défaut: taille du terminal hôte, sinon 24)
+        /// * `cols` - Nombre de colonnes du terminal (défaut: taille du terminal hôte, sinon 80)
         /// * `command` - Commande à exécuter (défaut: "claude")
         #[napi(constructor)]
         #[allow(clippy::cast_possible_truncation)] // Terminal size values are always < u16::MAX
         pub fn new(rows: Option<u32>, cols: Option<u32>, command: Option<String>) -> Result<Self> {
+            // Si l'appelant ne précise pas de géométrie, on interroge le terminal
+            // hôte plutôt que de se rabattre sur un 24x80 codé en dur; le PTY
+            // reste ensuite synchronisé via le watcher SIGWINCH de `PtyManager`.
+            let detected = query_controlling_terminal_size();
             let size = PtySize {
-                rows: rows.unwrap_or(24) as u16,
-                cols: cols.unwrap_or(80) as u16,
+                rows: rows.map_or_else(|| detected.map_or(24, |d| d.rows), |r| r as u16),
+                cols: cols.map_or_else(|| detected.map_or(80, |d| d.cols), |c| c as u16),
             };
 
             let cmd = command.unwrap_or_else(|| "claude".to_string());
@@ -154,10 +323,14 @@ mod napi_bindings {
 
             Ok(Self {
                 pty: Arc::new(Mutex::new(pty)),
-                analyzer: Arc::new(RwLock::new(StreamAnalyzer::new())),
+                analyzer: Arc::new(SharedAnalyzer::new()),
                 injector: Arc::new(RwLock::new(ContextInjector::new())),
+                filters: Arc::new(Mutex::new(FilterChain::new())),
+                profiler: Arc::new(SessionProfiler::new(false)),
                 config: Config::default(),
                 started_at: std::time::Instant::now(),
+                watch: Arc::new(Mutex::new(None)),
+                verbosity: Arc::new(Mutex::new(VerbosityFilter::new(SuggestionVerbosity::default()))),
             })
         }
 
@@ -169,6 +342,7 @@ mod napi_bindings {
             command: Option<String>,
             injection_interval_ms: Option<u32>,
             suggestions_enabled: Option<bool>,
+            profiling_enabled: Option<bool>,
         ) -> Result<Self> {
             let mut session = Self::new(rows, cols, command)?;
 
@@ -180,16 +354,26 @@ mod napi_bindings {
                 session.config.suggestions_enabled = enabled;
             }
 
+            if let Some(enabled) = profiling_enabled {
+                session.profiler = Arc::new(SessionProfiler::new(enabled));
+            }
+
             Ok(session)
         }
 
         /// Lit les données disponibles du PTY
         ///
         /// Retourne l'output brut (avec ANSI), l'output nettoyé, les suggestions et les statistiques.
+        ///
+        /// # Arguments
+        /// * `render_mode` - `"plain"` (défaut) pour le message court habituel, ou
+        ///   `"annotated"` pour citer les dernières lignes ayant déclenché la
+        ///   suggestion avec un rang de carets (voir `Suggestion::format_annotated`)
         #[napi]
-        pub async fn read(&self) -> Result<ReadResult> {
+        pub async fn read(&self, render_mode: Option<String>) -> Result<ReadResult> {
             // Scope 1: PTY lock seulement pour la lecture
             let output_bytes = {
+                let _profile = self.profiler.scope("pty_read");
                 let pty = self.pty.lock().await;
                 pty.read_async().await.map_err(napi::Error::from)?
             }; // Lock PTY libéré ici
@@ -205,24 +389,70 @@ mod napi_bindings {
                     token_estimate: 0,
                     detected_types: vec!["empty".to_string()],
                     total_size: 0,
+                    lines_saved: 0,
+                    tokens_saved: 0,
                 });
             }
 
             // Scope 2: Analyzer write lock seulement pour l'analyse
+            // On passe les bytes bruts (pas `raw_output`): l'écran virtuel vt100
+            // recolle correctement les séquences d'échappement coupées entre
+            // deux reads du PTY.
             let analysis = {
-                let mut analyzer = self.analyzer.write().await;
-                analyzer.analyze(&raw_output)
-            }; // Lock analyzer libéré ici
+                let _profile = self.profiler.scope("analyze");
+                self.analyzer.feed(&output_bytes).await
+            };
 
             // Scope 3: Injector write lock seulement pour les suggestions
             let suggestions = if self.config.suggestions_enabled {
+                let _profile = self.profiler.scope("inject");
+                let annotated = render_mode.as_deref() == Some("annotated");
+                let context = if annotated {
+                    Some(self.analyzer.recent_lines(ANNOTATED_CONTEXT_LINES).await)
+                } else {
+                    None
+                };
+
                 let mut injector = self.injector.write().await;
-                analysis
+                let generated: Vec<_> = analysis
                     .content_types
                     .iter()
-                    .filter_map(|ct| injector.generate_suggestion(ct))
-                    .map(|s| s.format_for_display())
-                    .collect()
+                    .filter_map(|ct| injector.generate_suggestion(ct).map(|s| (s, ct)))
+                    .collect();
+                drop(injector);
+
+                for (suggestion, _) in &generated {
+                    self.analyzer
+                        .record_suggestion(suggestion.suggestion_type.as_str())
+                        .await;
+                }
+
+                if let Some(ctx) = context.as_deref() {
+                    // Mode annotated (chunk3-5): citations de contexte par suggestion,
+                    // pas de place pour une ligne comptée collapsée - verbosité ignorée.
+                    generated
+                        .into_iter()
+                        .map(|(s, _)| SuggestionInfo {
+                            code: s.code.to_string(),
+                            message: s.format_annotated(ctx),
+                        })
+                        .collect()
+                } else {
+                    let mut verbosity = self.verbosity.lock().await;
+                    generated
+                        .into_iter()
+                        .filter_map(|(s, ct)| {
+                            let size_bytes = match ct {
+                                ContentType::LargeOutput { size } => Some(*size),
+                                _ => None,
+                            };
+                            verbosity.filter(&s, size_bytes).map(|message| SuggestionInfo {
+                                code: s.code.to_string(),
+                                message,
+                            })
+                        })
+                        .collect()
+                }
             } else {
                 Vec::new()
             }; // Lock injector libéré ici
@@ -242,21 +472,180 @@ mod napi_bindings {
                 token_estimate: analysis.token_estimate as u32,
                 detected_types,
                 total_size: analysis.total_size as u32,
+                lines_saved: analysis.lines_saved as u32,
+                tokens_saved: analysis.tokens_saved as u32,
             })
         }
 
+        /// Variante JSON newline-delimited de [`Self::read`]
+        ///
+        /// Même analyse/injection que `read()`, mais le résultat et chaque
+        /// suggestion générée sont sérialisés en `SessionEvent` via `serde`
+        /// plutôt que formatés avec `Suggestion::format_for_display`, pour
+        /// les wrappers qui veulent alimenter une pipeline de logging/télémétrie.
+        #[napi]
+        pub async fn read_json(&self) -> Result<String> {
+            // Scope 1: PTY lock seulement pour la lecture
+            let output_bytes = {
+                let _profile = self.profiler.scope("pty_read");
+                let pty = self.pty.lock().await;
+                pty.read_async().await.map_err(napi::Error::from)?
+            }; // Lock PTY libéré ici
+
+            if output_bytes.is_empty() {
+                return Ok(String::new());
+            }
+
+            // Scope 2: Analyzer write lock seulement pour l'analyse
+            let analysis = {
+                let _profile = self.profiler.scope("analyze");
+                self.analyzer.feed(&output_bytes).await
+            };
+
+            // Scope 3: Injector write lock seulement pour les suggestions
+            let suggestions = if self.config.suggestions_enabled {
+                let _profile = self.profiler.scope("inject");
+                let mut injector = self.injector.write().await;
+                let generated: Vec<_> = analysis
+                    .content_types
+                    .iter()
+                    .filter_map(|ct| injector.generate_suggestion(ct))
+                    .collect();
+                drop(injector);
+
+                for suggestion in &generated {
+                    self.analyzer
+                        .record_suggestion(suggestion.suggestion_type.as_str())
+                        .await;
+                }
+
+                generated
+            } else {
+                Vec::new()
+            }; // Lock injector libéré ici
+
+            let detected_types: Vec<String> = analysis
+                .content_types
+                .iter()
+                .map(|ct| format!("{ct:?}"))
+                .collect();
+
+            let estimator = TokenEstimator::new();
+            let mut lines = Vec::with_capacity(1 + suggestions.len());
+
+            lines.push(
+                SessionEvent::Read {
+                    token_estimate: analysis.token_estimate,
+                    detected_types,
+                }
+                .to_json_line(),
+            );
+
+            for suggestion in &suggestions {
+                lines.push(
+                    SessionEvent::Suggestion {
+                        suggestion_type: format!("{:?}", suggestion.suggestion_type),
+                        tokens: templates::estimated_tokens_saved(&suggestion.suggestion_type, &estimator),
+                        message: suggestion.display_message.clone(),
+                    }
+                    .to_json_line(),
+                );
+            }
+
+            let lines: Vec<String> = lines
+                .into_iter()
+                .collect::<serde_json::Result<_>>()
+                .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+
+            Ok(lines.join("\n"))
+        }
+
+        /// Variante "rapport combiné" de [`Self::read_json`]
+        ///
+        /// Au lieu d'un `Read` suivi d'un `Suggestion` par ligne, restitue un
+        /// unique objet `AnalysisReport` portant à la fois les types de
+        /// contenu détectés et, pour chaque suggestion émise, les champs
+        /// contextuels (`error_count`/`tool`, `size_bytes`, `file_path`) qui
+        /// ont déclenché la suggestion — pour les outils qui veulent parser
+        /// exactement quelle optimisation MCP a été suggérée et pourquoi
+        /// sans reconstruire la paire type-de-contenu/suggestion eux-mêmes.
+        #[napi]
+        pub async fn read_report(&self) -> Result<String> {
+            // Scope 1: PTY lock seulement pour la lecture
+            let output_bytes = {
+                let _profile = self.profiler.scope("pty_read");
+                let pty = self.pty.lock().await;
+                pty.read_async().await.map_err(napi::Error::from)?
+            }; // Lock PTY libéré ici
+
+            if output_bytes.is_empty() {
+                return Ok(String::new());
+            }
+
+            // Scope 2: Analyzer write lock seulement pour l'analyse
+            let analysis = {
+                let _profile = self.profiler.scope("analyze");
+                self.analyzer.feed(&output_bytes).await
+            };
+
+            // Scope 3: Injector write lock seulement pour les suggestions
+            let suggestion_reports = if self.config.suggestions_enabled {
+                let _profile = self.profiler.scope("inject");
+                let mut injector = self.injector.write().await;
+                let generated: Vec<_> = analysis
+                    .content_types
+                    .iter()
+                    .filter_map(|ct| injector.generate_suggestion(ct).map(|s| (s, ct)))
+                    .collect();
+                drop(injector);
+
+                let mut reports = Vec::with_capacity(generated.len());
+                for (suggestion, ct) in generated {
+                    self.analyzer
+                        .record_suggestion(suggestion.suggestion_type.as_str())
+                        .await;
+                    reports.push(suggestion.to_report(ct));
+                }
+
+                reports
+            } else {
+                Vec::new()
+            }; // Lock injector libéré ici
+
+            let report = AnalysisReport::new(&analysis, suggestion_reports);
+            report
+                .to_json_line()
+                .map_err(|e| napi::Error::from_reason(e.to_string()))
+        }
+
         /// Écrit des données dans le PTY (stdin de Claude)
+        ///
+        /// Le chunk traverse d'abord la chaîne de filtres d'entrée
+        /// (`FilterChain`) qui peut le laisser passer, le réécrire, l'avaler
+        /// ou y injecter des bytes supplémentaires.
         #[napi]
         pub async fn write(&self, data: String) -> Result<()> {
+            let filtered = {
+                let mut filters = self.filters.lock().await;
+                filters.apply(data.as_bytes())
+            }; // Lock filters libéré ici
+
             let pty = self.pty.lock().await;
-            pty.write_str(&data).await.map_err(napi::Error::from)
+            pty.write(&filtered).await.map_err(napi::Error::from)
         }
 
         /// Écrit des bytes bruts dans le PTY
+        ///
+        /// Traverse la même chaîne de filtres que [`Self::write`].
         #[napi]
         pub async fn write_bytes(&self, data: Buffer) -> Result<()> {
+            let filtered = {
+                let mut filters = self.filters.lock().await;
+                filters.apply(&data)
+            }; // Lock filters libéré ici
+
             let pty = self.pty.lock().await;
-            pty.write(&data).await.map_err(napi::Error::from)
+            pty.write(&filtered).await.map_err(napi::Error::from)
         }
 
         /// Vérifie si le process est toujours en cours d'exécution
@@ -298,17 +687,115 @@ mod napi_bindings {
         #[allow(clippy::cast_possible_truncation)] // Stats values bounded by practical limits
         pub async fn stats(&self) -> SessionStats {
             // Read locks - peuvent être acquis en parallèle avec d'autres reads
-            let analyzer = self.analyzer.read().await;
+            let snapshot = self.analyzer.snapshot().await;
             let injector = self.injector.read().await;
 
             SessionStats {
-                total_tokens: analyzer.total_tokens() as u32,
+                total_tokens: snapshot.total_tokens as u32,
                 total_suggestions: injector.total_suggestions() as u32,
-                total_build_errors: analyzer.total_errors() as u32,
+                total_build_errors: snapshot.total_errors as u32,
                 elapsed_ms: self.started_at.elapsed().as_millis() as u32,
             }
         }
 
+        /// Retourne la ventilation des stats par type de contenu détecté
+        ///
+        /// Combine `StreamAnalyzer::content_type_stats` (occurrences/tokens)
+        /// et `ContextInjector::kind_stats` (émis/throttled) par discriminant
+        /// de `ContentType`, réutilise le même chemin de lecture (`RwLock`)
+        /// que [`Self::stats`] donc safe à appeler pendant un `read()` concurrent.
+        #[napi]
+        #[allow(clippy::cast_possible_truncation)] // Stats values bounded by practical limits
+        pub async fn stats_breakdown(&self) -> Vec<ContentTypeStat> {
+            let content_stats = self.analyzer.content_type_stats().await;
+            let kind_stats = self.injector.read().await.kind_stats().clone();
+            let estimator = TokenEstimator::new();
+
+            let kinds: HashSet<&String> = content_stats.keys().chain(kind_stats.keys()).collect();
+
+            let mut rows: Vec<ContentTypeStat> = kinds
+                .into_iter()
+                .map(|kind| {
+                    let content = content_stats.get(kind).copied().unwrap_or_default();
+                    let injection = kind_stats.get(kind).copied().unwrap_or_default();
+
+                    let estimated_tokens_saved = SuggestionType::from_content_kind(kind).map_or(0, |st| {
+                        templates::estimated_tokens_saved(&st, &estimator) * injection.emitted
+                    });
+
+                    ContentTypeStat {
+                        kind: kind.clone(),
+                        occurrences: content.occurrences as u32,
+                        tokens: content.tokens as u32,
+                        suggestions_emitted: injection.emitted as u32,
+                        suggestions_throttled: injection.throttled as u32,
+                        estimated_tokens_saved: estimated_tokens_saved as u32,
+                    }
+                })
+                .collect();
+
+            rows.sort_by(|a, b| a.kind.cmp(&b.kind));
+            rows
+        }
+
+        /// Retourne les statistiques robustes (distributions taille/tokens,
+        /// erreurs, suggestions) accumulées depuis le dernier `reset_stats`
+        ///
+        /// Complète [`Self::stats`] (compteurs bruts) et [`Self::stats_breakdown`]
+        /// (ventilation par type de contenu) par une vue "distribution" de la
+        /// session entière (voir `stream::stats::SessionStats`).
+        #[napi]
+        #[allow(clippy::cast_possible_truncation)] // Stats values bounded by practical limits
+        pub async fn session_summary(&self) -> SessionStatsSummary {
+            let summary = self.analyzer.session_summary().await;
+
+            let mut tool_error_counts: Vec<CountRow> = summary
+                .tool_error_counts
+                .into_iter()
+                .map(|(key, count)| CountRow {
+                    key,
+                    count: count as u32,
+                })
+                .collect();
+            tool_error_counts.sort_by(|a, b| a.key.cmp(&b.key));
+
+            let mut suggestion_counts: Vec<CountRow> = summary
+                .suggestion_counts
+                .into_iter()
+                .map(|(key, count)| CountRow {
+                    key,
+                    count: count as u32,
+                })
+                .collect();
+            suggestion_counts.sort_by(|a, b| a.key.cmp(&b.key));
+
+            SessionStatsSummary {
+                total_bytes: summary.total_bytes as u32,
+                chunk_count: summary.chunk_count as u32,
+                chunk_size: summary.chunk_size.into(),
+                token_estimate: summary.token_estimate.into(),
+                tool_error_counts,
+                suggestion_counts,
+            }
+        }
+
+        /// Retourne les timings accumulés par phase de `read()` (vide si la
+        /// collecte n'a pas été activée via [`Self::with_config`])
+        #[napi]
+        #[allow(clippy::cast_possible_truncation)] // Timing values bounded by practical limits
+        pub async fn profile(&self) -> Vec<PhaseTiming> {
+            self.profiler
+                .timings()
+                .into_iter()
+                .map(|t| PhaseTiming {
+                    phase: t.phase,
+                    calls: t.calls as u32,
+                    total_us: t.total_us as u32,
+                    avg_us: t.avg_us as u32,
+                })
+                .collect()
+        }
+
         /// Active/désactive les suggestions
         #[napi]
         pub async fn set_suggestions_enabled(&self, enabled: bool) {
@@ -316,15 +803,146 @@ mod napi_bindings {
             injector.set_enabled(enabled);
         }
 
+        /// Fixe le niveau lint-style (et optionnellement le seuil et le
+        /// plafond par session) d'un type de suggestion, comme
+        /// `rustc -A`/`-W`/`-D` pour les lints
+        ///
+        /// `kind`: `"build_errors"`, `"large_output"`, `"file_read"` ou
+        /// `"prompt_reminder"`. `level`: `"allow"`, `"warn"` ou `"deny"`
+        /// (insensible à la casse). `Allow` supprime entièrement la
+        /// suggestion, `Warn` est le comportement par défaut, `Deny` escalade
+        /// (bypasse le throttle et l'anti-répétition, y compris le plafond
+        /// par session). `max_per_session`: `0` désactive explicitement le
+        /// plafond (illimité), sinon fixe le nombre maximal d'injections de
+        /// ce type pour la session (voir `ContextInjector::classify`).
+        #[napi]
+        pub async fn set_suggestion_level(
+            &self,
+            kind: String,
+            level: String,
+            threshold: Option<u32>,
+            max_per_session: Option<u32>,
+        ) -> Result<()> {
+            let suggestion_type = SuggestionType::parse(&kind)
+                .ok_or_else(|| napi::Error::from_reason(format!("unknown suggestion kind: {kind}")))?;
+            let level = Level::parse(&level)
+                .ok_or_else(|| napi::Error::from_reason(format!("unknown suggestion level: {level}")))?;
+
+            let mut injector = self.injector.write().await;
+            let config = injector.config_mut();
+            config.set_level(&suggestion_type, level);
+            if let Some(threshold) = threshold {
+                config.set_threshold(&suggestion_type, threshold as usize);
+            }
+            if let Some(max_per_session) = max_per_session {
+                let max = if max_per_session == 0 {
+                    None
+                } else {
+                    Some(max_per_session as usize)
+                };
+                config.set_max_per_session(&suggestion_type, max);
+            }
+
+            Ok(())
+        }
+
+        /// Fixe la verbosité des suggestions affichées par [`Self::read`],
+        /// façon pretty/terse/quiet de `libtest` (voir `injector::verbosity`)
+        ///
+        /// `mode`: `"quiet"` (n'affiche que `build_errors`), `"terse"`
+        /// (fusionne les répétitions d'un même type en une ligne comptée) ou
+        /// `"pretty"` (défaut, comportement historique), insensible à la casse.
+        ///
+        /// En quittant le mode `Terse`, les groupes encore en cours de
+        /// coalescence sont flush et retournés: sinon ils resteraient
+        /// piégés dans un mode qui ne les affichera jamais (voir
+        /// [`VerbosityFilter::flush`]).
+        #[napi]
+        pub async fn set_verbosity(&self, mode: String) -> Result<Vec<String>> {
+            let new_verbosity = SuggestionVerbosity::parse(&mode)
+                .ok_or_else(|| napi::Error::from_reason(format!("unknown verbosity mode: {mode}")))?;
+
+            let mut verbosity = self.verbosity.lock().await;
+            let flushed = if new_verbosity == SuggestionVerbosity::Terse {
+                Vec::new()
+            } else {
+                verbosity.flush()
+            };
+            verbosity.set_verbosity(new_verbosity);
+            Ok(flushed)
+        }
+
+        /// Flush les groupes de suggestions encore en cours de coalescence
+        /// en mode `Terse` (voir [`VerbosityFilter::flush`])
+        ///
+        /// À appeler en fin de session, juste avant [`Self::kill`] ou après
+        /// que [`Self::wait`] retourne: sans ça, le dernier groupe de
+        /// chaque type ne serait jamais affiché puisque [`Self::read`] ne le
+        /// flush que quand une nouvelle suggestion du même type arrive.
+        #[napi]
+        pub async fn flush_suggestions(&self) -> Vec<String> {
+            self.verbosity.lock().await.flush()
+        }
+
         /// Reset les compteurs de session
         #[napi]
-        #[allow(clippy::significant_drop_tightening)] // Both locks needed for atomic reset
         pub async fn reset_stats(&self) {
-            // Write locks - ordre constant: analyzer puis injector
-            let mut analyzer = self.analyzer.write().await;
-            let mut injector = self.injector.write().await;
-            analyzer.reset();
-            injector.reset();
+            // Ordre constant: analyzer puis injector
+            self.analyzer.reset().await;
+            self.injector.write().await.reset();
+        }
+
+        /// Démarre la surveillance de `paths` (fichiers ou dossiers,
+        /// récursif) pour relancer la commande wrappée à chaque rafale de
+        /// modifications (voir `pty::watch`)
+        ///
+        /// `debounce_ms` fixe la fenêtre de coalescence (défaut: 300ms, voir
+        /// `WatchConfig::default`). Un second appel remplace la surveillance
+        /// précédente; le `poll_watch` associé doit être appelé
+        /// périodiquement (ex: à chaque tick de `read()`) pour consommer les
+        /// restarts détectés.
+        #[napi]
+        pub async fn watch(&self, paths: Vec<String>, debounce_ms: Option<u32>) -> Result<()> {
+            let config = WatchConfig {
+                paths: paths.into_iter().map(PathBuf::from).collect(),
+                debounce: debounce_ms.map_or_else(
+                    || WatchConfig::default().debounce,
+                    |ms| Duration::from_millis(u64::from(ms)),
+                ),
+            };
+
+            let (handle, rx) = spawn_watcher(&config).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+
+            *self.watch.lock().await = Some((handle, rx));
+            Ok(())
+        }
+
+        /// Consomme un restart détecté par [`Self::watch`]: tue et relance
+        /// la commande wrappée, puis retourne un rappel à afficher
+        ///
+        /// Ne bloque jamais: retourne `None` si `watch` n'a pas été appelé
+        /// ou qu'aucun changement n'est survenu depuis le dernier poll.
+        #[napi]
+        pub async fn poll_watch(&self) -> Result<Option<SuggestionInfo>> {
+            let mut guard = self.watch.lock().await;
+            let Some((_, rx)) = guard.as_mut() else {
+                return Ok(None);
+            };
+
+            let Ok(changed_files) = rx.try_recv() else {
+                return Ok(None);
+            };
+            drop(guard);
+
+            let mut pty = self.pty.lock().await;
+            pty.respawn().await.map_err(napi::Error::from)?;
+            drop(pty);
+
+            let suggestion = Suggestion::watch_restart(changed_files);
+            Ok(Some(SuggestionInfo {
+                code: suggestion.code.to_string(),
+                message: suggestion.format_for_display(),
+            }))
         }
     }
 
@@ -332,7 +950,6 @@ mod napi_bindings {
     #[napi]
     pub mod utils {
         use crate::injector::ContextInjector;
-        use crate::stream;
         use crate::tokens::TokenEstimator;
 
         /// Estime le nombre de tokens pour un texte
@@ -350,11 +967,31 @@ mod napi_bindings {
             ContextInjector::is_code_file(&path)
         }
 
+        /// Explique un code de suggestion stable (ex: `D0001`, voir
+        /// `ReadResult.suggestions[].code`): retourne la justification de
+        /// détection et la commande d'optimisation MCP recommandée,
+        /// `None` si le code est inconnu
+        #[napi]
+        #[allow(clippy::needless_pass_by_value)] // NAPI requires owned types at JS boundary
+        pub fn explain(code: String) -> Option<String> {
+            crate::injector::explain(&code).map(str::to_string)
+        }
+
+        /// Regex de secours pour l'utilitaire `strip_ansi` exposé côté JS.
+        ///
+        /// L'analyseur de flux n'utilise plus de regex pour retirer les ANSI
+        /// (il passe par l'écran virtuel `vt100`, voir `stream::screen`), mais
+        /// cet utilitaire est un simple strip de texte arbitraire côté JS, pas
+        /// une détection de contenu: pas besoin d'un écran virtuel complet ici.
+        static ANSI_ESCAPE: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+            regex::Regex::new(r"\x1b\[[0-9;]*[a-zA-Z]|\x1b\].*?\x07").unwrap()
+        });
+
         /// Retire les codes ANSI d'un texte
         #[napi]
         #[allow(clippy::needless_pass_by_value)] // NAPI requires owned types at JS boundary
         pub fn strip_ansi(text: String) -> String {
-            stream::PATTERNS.ansi_escape.replace_all(&text, "").to_string()
+            ANSI_ESCAPE.replace_all(&text, "").to_string()
         }
     }
 
@@ -0,0 +1,319 @@
+//! Orchestrateur multi-sessions
+//!
+//! Spawn et pilote plusieurs `PtyManager` en parallèle (ex: Claude sur
+//! plusieurs repos/tâches en simultané), avec une limite de concurrence
+//! configurable et un backfill automatique depuis une file d'attente quand
+//! une session se termine.
+
+use std::collections::VecDeque;
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::pty::{PtyError, PtyManager, PtySize};
+use crate::stream::{ContentType, StreamAnalyzer};
+
+/// Graine par défaut du RNG utilisé pour mélanger l'ordre de lancement
+/// initial des sessions en file; évite les artefacts de scheduling en
+/// lock-step quand beaucoup de sessions démarrent au même instant.
+const DEFAULT_RNG_SEED: u64 = 0x5EED_C0DE;
+
+/// Configuration du pool de sessions
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Nombre maximum de sessions actives en simultané
+    pub max_concurrency: usize,
+
+    /// Graine du RNG utilisé pour mélanger l'ordre de lancement initial
+    pub rng_seed: u64,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 8,
+            rng_seed: DEFAULT_RNG_SEED,
+        }
+    }
+}
+
+/// Commande en attente de lancement dans le pool
+#[derive(Debug, Clone)]
+pub struct QueuedSession {
+    /// Commande à exécuter (ex: "claude")
+    pub command: String,
+    /// Arguments de la commande
+    pub args: Vec<String>,
+    /// Taille du PTY à allouer
+    pub size: PtySize,
+}
+
+/// Statut exposé d'une session active ou venant de se terminer
+#[derive(Debug, Clone)]
+pub struct SessionStatus {
+    /// `true` si le child process tourne encore
+    pub is_running: bool,
+    /// Dernier type de contenu détecté par l'analyseur de cette session
+    pub last_content_type: Option<ContentType>,
+    /// Code de sortie, renseigné une fois la session terminée et reapée
+    pub exit_code: Option<u32>,
+}
+
+/// Session active suivie par le pool
+struct ActiveSession {
+    id: usize,
+    pty: PtyManager,
+    analyzer: StreamAnalyzer,
+    last_content_type: Option<ContentType>,
+}
+
+/// Rapport retourné après un cycle de poll: une entrée par session active
+/// ou qui vient d'être reapée
+pub struct SessionReport {
+    /// Identifiant de la session (stable du lancement jusqu'au reap)
+    pub id: usize,
+    /// Statut courant de la session
+    pub status: SessionStatus,
+}
+
+/// Orchestrateur pilotant un ensemble de `PtyManager` en parallèle
+pub struct SessionPool {
+    config: PoolConfig,
+    queue: VecDeque<QueuedSession>,
+    active: Vec<ActiveSession>,
+    next_id: usize,
+}
+
+impl SessionPool {
+    /// Crée un pool vide et relève la limite de fd du process (Unix) pour
+    /// que les gros pools n'échouent pas avec "too many open files"
+    pub fn new(config: PoolConfig) -> Self {
+        raise_fd_limit();
+
+        Self {
+            config,
+            queue: VecDeque::new(),
+            active: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Ajoute une commande en file d'attente
+    pub fn enqueue(&mut self, command: String, args: Vec<String>, size: PtySize) {
+        self.queue.push_back(QueuedSession {
+            command,
+            args,
+            size,
+        });
+    }
+
+    /// Nombre de sessions actives
+    #[allow(dead_code)]
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Nombre de sessions en attente
+    #[allow(dead_code)]
+    pub fn queued_count(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Mélange l'ordre de lancement initial de la file (RNG seedé pour
+    /// rester reproductible) puis lance des sessions jusqu'à
+    /// `max_concurrency` ou épuisement de la file.
+    pub fn start(&mut self) -> Result<(), PtyError> {
+        self.shuffle_queue();
+        self.fill_from_queue()
+    }
+
+    fn shuffle_queue(&mut self) {
+        let mut rng = SmallRng::seed_from_u64(self.config.rng_seed);
+        let mut items: Vec<QueuedSession> = self.queue.drain(..).collect();
+
+        // Fisher-Yates
+        for i in (1..items.len()).rev() {
+            let j = rng.gen_range(0..=i);
+            items.swap(i, j);
+        }
+
+        self.queue = items.into();
+    }
+
+    fn fill_from_queue(&mut self) -> Result<(), PtyError> {
+        while self.active.len() < self.config.max_concurrency {
+            let Some(queued) = self.queue.pop_front() else {
+                break;
+            };
+            self.spawn(queued)?;
+        }
+        Ok(())
+    }
+
+    fn spawn(&mut self, queued: QueuedSession) -> Result<(), PtyError> {
+        let args: Vec<&str> = queued.args.iter().map(String::as_str).collect();
+        let pty = PtyManager::new(&queued.command, &args, queued.size)?;
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.active.push(ActiveSession {
+            id,
+            pty,
+            analyzer: StreamAnalyzer::new(),
+            last_content_type: None,
+        });
+
+        Ok(())
+    }
+
+    /// Lit et analyse un chunk de chaque session active, reape celles qui
+    /// sont terminées et relance des sessions en attente pour combler les
+    /// places libérées.
+    ///
+    /// Retourne un rapport par session (actives incluses, terminées
+    /// incluses) pour ce cycle de poll.
+    pub async fn poll_once(&mut self) -> Vec<SessionReport> {
+        let mut reports = Vec::with_capacity(self.active.len());
+        let mut finished_indices = Vec::new();
+
+        for (index, session) in self.active.iter_mut().enumerate() {
+            if let Ok(chunk) = session.pty.read_async().await {
+                if !chunk.is_empty() {
+                    let result = session.analyzer.analyze(&chunk);
+                    if let Some(content_type) = result
+                        .content_types
+                        .into_iter()
+                        .find(|ct| !matches!(ct, ContentType::Normal))
+                    {
+                        session.last_content_type = Some(content_type);
+                    }
+                }
+            }
+
+            let is_running = session.pty.is_running().await;
+            if !is_running {
+                finished_indices.push(index);
+            }
+
+            reports.push(SessionReport {
+                id: session.id,
+                status: SessionStatus {
+                    is_running,
+                    last_content_type: session.last_content_type.clone(),
+                    exit_code: None,
+                },
+            });
+        }
+
+        // Reap en partant de la fin pour ne pas invalider les index restants
+        for index in finished_indices.into_iter().rev() {
+            let session = self.active.remove(index);
+            let exit_code = session.pty.wait().await.ok();
+
+            if let Some(report) = reports.iter_mut().find(|r| r.id == session.id) {
+                report.status.exit_code = exit_code;
+            }
+        }
+
+        let _ = self.fill_from_queue();
+
+        reports
+    }
+}
+
+/// Relève la limite soft `RLIMIT_NOFILE` vers la limite hard au démarrage,
+/// pour qu'un pool de beaucoup de sessions (chacune consommant plusieurs fd
+/// pour son PTY + son thread de lecture) ne se heurte pas à "too many open
+/// files".
+#[cfg(unix)]
+fn raise_fd_limit() {
+    // SAFETY: `rlimit` est un type POD, `getrlimit`/`setrlimit` ne touchent
+    // qu'à ce buffer et à la limite de fd du process courant.
+    unsafe {
+        let mut limit: libc::rlimit = std::mem::zeroed();
+        if libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) != 0 {
+            return;
+        }
+
+        let mut target = limit.rlim_max;
+
+        // macOS refuse `RLIM_INFINITY` (et les valeurs au-delà d'`OPEN_MAX`)
+        // pour `RLIMIT_NOFILE` et renvoie EINVAL; on clampe pour rester valide.
+        #[cfg(target_os = "macos")]
+        {
+            let open_max = libc::OPEN_MAX as libc::rlim_t;
+            if target == libc::RLIM_INFINITY || target > open_max {
+                target = open_max;
+            }
+        }
+
+        if target > limit.rlim_cur {
+            limit.rlim_cur = target;
+            let _ = libc::setrlimit(libc::RLIMIT_NOFILE, &limit);
+        }
+    }
+}
+
+/// No-op sur les plateformes non-Unix: pas de `RLIMIT_NOFILE`
+#[cfg(not(unix))]
+fn raise_fd_limit() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_config_default() {
+        let config = PoolConfig::default();
+        assert_eq!(config.max_concurrency, 8);
+    }
+
+    #[test]
+    fn test_raise_fd_limit_does_not_panic() {
+        raise_fd_limit();
+    }
+
+    #[tokio::test]
+    async fn test_pool_respects_max_concurrency_and_backfills() {
+        let mut pool = SessionPool::new(PoolConfig {
+            max_concurrency: 1,
+            rng_seed: 42,
+        });
+
+        pool.enqueue("echo".to_string(), vec!["one".to_string()], PtySize::default());
+        pool.enqueue("echo".to_string(), vec!["two".to_string()], PtySize::default());
+
+        pool.start().expect("Failed to start pool");
+        assert_eq!(pool.active_count(), 1);
+        assert_eq!(pool.queued_count(), 1);
+
+        // Laisser le premier echo se terminer
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+        // Plusieurs cycles: un pour détecter la fin + reap, un pour backfill
+        let mut reports = pool.poll_once().await;
+        for _ in 0..5 {
+            if pool.queued_count() == 0 {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+            reports = pool.poll_once().await;
+        }
+
+        assert!(reports.iter().any(|r| r.status.exit_code.is_some()));
+        assert_eq!(pool.queued_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_reports_exit_code() {
+        let mut pool = SessionPool::new(PoolConfig::default());
+        pool.enqueue("echo".to_string(), vec!["done".to_string()], PtySize::default());
+        pool.start().expect("Failed to start pool");
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+        let reports = pool.poll_once().await;
+
+        assert!(reports.iter().any(|r| r.status.exit_code == Some(0)));
+    }
+}
@@ -0,0 +1,156 @@
+//! Auto-profiling des phases internes d'une session PTY
+//!
+//! Modélisé sur le `SelfProfiler`/`SelfProfilerRef` de rustc: un guard
+//! scoped autour de chaque phase accumule temps total et nombre d'appels
+//! dans une table nommée, avec un coût nul quand la collecte est désactivée
+//! (le cas par défaut, via le flag de construction de `CtxOptSession`). Les
+//! noms de phase restent stables (`"pty_read"`, `"analyze"`, `"inject"`)
+//! pour qu'un outil externe puisse diffé­rencier deux runs, comme le fait
+//! `-Z self-profile` côté rustc.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct PhaseAccumulator {
+    calls: u64,
+    total: Duration,
+}
+
+/// Profileur léger des phases internes de `CtxOptSession::read`
+///
+/// La table de compteurs vit derrière un `Mutex` synchrone (pas `tokio::Mutex`):
+/// `PhaseGuard::drop` n'est jamais `async`, donc le verrou ne peut être tenu
+/// qu'en section critique courte et synchrone, jamais à travers un `.await`.
+#[derive(Debug)]
+pub struct SessionProfiler {
+    enabled: bool,
+    phases: Mutex<HashMap<&'static str, PhaseAccumulator>>,
+}
+
+impl SessionProfiler {
+    /// Crée un profileur; `enabled = false` garde `scope` à coût nul
+    /// (aucune mesure, aucune allocation)
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            phases: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Démarre un timer scoped pour `phase`; l'accumulation se fait au drop
+    /// du guard retourné. No-op si la collecte est désactivée.
+    pub fn scope(&self, phase: &'static str) -> PhaseGuard<'_> {
+        PhaseGuard {
+            profiler: self.enabled.then_some(self),
+            phase,
+            start: Instant::now(),
+        }
+    }
+
+    fn record(&self, phase: &'static str, elapsed: Duration) {
+        let mut phases = self.phases.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let acc = phases.entry(phase).or_default();
+        acc.calls += 1;
+        acc.total += elapsed;
+    }
+
+    /// Retourne les timings accumulés jusqu'ici, triés par nom de phase
+    pub fn timings(&self) -> Vec<PhaseTiming> {
+        let phases = self.phases.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut timings: Vec<PhaseTiming> = phases
+            .iter()
+            .map(|(phase, acc)| {
+                let total_us = u64::try_from(acc.total.as_micros()).unwrap_or(u64::MAX);
+                let avg_us = if acc.calls == 0 { 0 } else { total_us / acc.calls };
+                PhaseTiming {
+                    phase: (*phase).to_string(),
+                    calls: acc.calls,
+                    total_us,
+                    avg_us,
+                }
+            })
+            .collect();
+        timings.sort_by(|a, b| a.phase.cmp(&b.phase));
+        timings
+    }
+}
+
+impl Default for SessionProfiler {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+/// Guard retourné par `SessionProfiler::scope`; accumule la durée écoulée
+/// dans le `SessionProfiler` au moment où il est droppé
+pub struct PhaseGuard<'a> {
+    profiler: Option<&'a SessionProfiler>,
+    phase: &'static str,
+    start: Instant,
+}
+
+impl Drop for PhaseGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(profiler) = self.profiler {
+            profiler.record(self.phase, self.start.elapsed());
+        }
+    }
+}
+
+/// Timing agrégé d'une phase, prêt à être exposé côté Node.js
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub phase: String,
+    pub calls: u64,
+    pub total_us: u64,
+    pub avg_us: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn test_disabled_profiler_records_nothing() {
+        let profiler = SessionProfiler::new(false);
+        {
+            let _guard = profiler.scope("pty_read");
+            sleep(Duration::from_millis(1));
+        }
+        assert!(profiler.timings().is_empty());
+    }
+
+    #[test]
+    fn test_enabled_profiler_accumulates_calls_and_time() {
+        let profiler = SessionProfiler::new(true);
+        {
+            let _guard = profiler.scope("analyze");
+            sleep(Duration::from_millis(1));
+        }
+        {
+            let _guard = profiler.scope("analyze");
+            sleep(Duration::from_millis(1));
+        }
+
+        let timings = profiler.timings();
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].phase, "analyze");
+        assert_eq!(timings[0].calls, 2);
+        assert!(timings[0].total_us > 0);
+        assert_eq!(timings[0].avg_us, timings[0].total_us / 2);
+    }
+
+    #[test]
+    fn test_timings_sorted_by_phase_name() {
+        let profiler = SessionProfiler::new(true);
+        drop(profiler.scope("pty_read"));
+        drop(profiler.scope("analyze"));
+        drop(profiler.scope("inject"));
+
+        let phases: Vec<String> = profiler.timings().into_iter().map(|t| t.phase).collect();
+        assert_eq!(phases, vec!["analyze", "inject", "pty_read"]);
+    }
+}
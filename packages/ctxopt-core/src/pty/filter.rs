@@ -0,0 +1,147 @@
+//! Middleware d'interception des entrées clavier
+//!
+//! S'intercale entre le stdin de l'utilisateur (en raw mode via
+//! `RawModeGuard`) et `PtyManager::write`: chaque filtre enregistré peut
+//! laisser passer, réécrire, avaler ou injecter des bytes avant qu'ils
+//! n'atteignent le child process. Permet par exemple des macros de
+//! raccourcis clavier, des garde-fous de paste, ou l'auto-réponse à des
+//! prompts.
+
+/// Action décidée par un `InputFilter` pour un chunk de bytes donné
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterAction {
+    /// Laisse passer les bytes tels quels
+    Forward,
+
+    /// Remplace les bytes par ceux fournis (un `Vec` vide avale l'entrée)
+    Replace(Vec<u8>),
+
+    /// Laisse passer les bytes tels quels et injecte des bytes supplémentaires
+    /// juste après
+    Inject(Vec<u8>),
+}
+
+/// Filtre d'entrée appliqué à chaque chunk de keystrokes avant écriture au PTY
+pub trait InputFilter: Send {
+    /// Examine (et éventuellement transforme) un chunk de bytes
+    fn on_input(&mut self, bytes: &[u8]) -> FilterAction;
+}
+
+/// Chaîne ordonnée de filtres appliqués successivement à chaque chunk
+///
+/// Chaque filtre reçoit le résultat (potentiellement réécrit) du filtre
+/// précédent; les bytes injectés par un filtre sont accumulés et ajoutés
+/// après le résultat final de la chaîne.
+#[derive(Default)]
+pub struct FilterChain {
+    filters: Vec<Box<dyn InputFilter>>,
+}
+
+impl FilterChain {
+    /// Crée une chaîne de filtres vide
+    pub fn new() -> Self {
+        Self {
+            filters: Vec::new(),
+        }
+    }
+
+    /// Enregistre un filtre en fin de chaîne
+    pub fn register(&mut self, filter: Box<dyn InputFilter>) {
+        self.filters.push(filter);
+    }
+
+    /// Fait passer un chunk de bytes à travers tous les filtres enregistrés
+    ///
+    /// Retourne les bytes à effectivement écrire dans le PTY (chunk
+    /// transformé, suivi des éventuelles injections).
+    pub fn apply(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let mut current = bytes.to_vec();
+        let mut injected = Vec::new();
+
+        for filter in &mut self.filters {
+            match filter.on_input(&current) {
+                FilterAction::Forward => {}
+                FilterAction::Replace(new_bytes) => current = new_bytes,
+                FilterAction::Inject(extra) => injected.extend(extra),
+            }
+        }
+
+        current.extend(injected);
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Uppercase;
+    impl InputFilter for Uppercase {
+        fn on_input(&mut self, bytes: &[u8]) -> FilterAction {
+            FilterAction::Replace(bytes.to_ascii_uppercase())
+        }
+    }
+
+    struct Swallow;
+    impl InputFilter for Swallow {
+        fn on_input(&mut self, _bytes: &[u8]) -> FilterAction {
+            FilterAction::Replace(Vec::new())
+        }
+    }
+
+    struct AutoAnswerYes;
+    impl InputFilter for AutoAnswerYes {
+        fn on_input(&mut self, _bytes: &[u8]) -> FilterAction {
+            FilterAction::Inject(b"y\n".to_vec())
+        }
+    }
+
+    #[test]
+    fn test_empty_chain_forwards_unchanged() {
+        let mut chain = FilterChain::new();
+        assert_eq!(chain.apply(b"hello"), b"hello");
+    }
+
+    #[test]
+    fn test_forward_keeps_bytes() {
+        struct NoOp;
+        impl InputFilter for NoOp {
+            fn on_input(&mut self, _bytes: &[u8]) -> FilterAction {
+                FilterAction::Forward
+            }
+        }
+
+        let mut chain = FilterChain::new();
+        chain.register(Box::new(NoOp));
+        assert_eq!(chain.apply(b"hello"), b"hello");
+    }
+
+    #[test]
+    fn test_replace_rewrites_bytes() {
+        let mut chain = FilterChain::new();
+        chain.register(Box::new(Uppercase));
+        assert_eq!(chain.apply(b"hello"), b"HELLO");
+    }
+
+    #[test]
+    fn test_replace_empty_swallows_input() {
+        let mut chain = FilterChain::new();
+        chain.register(Box::new(Swallow));
+        assert_eq!(chain.apply(b"hello"), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_inject_appends_after_forwarded_bytes() {
+        let mut chain = FilterChain::new();
+        chain.register(Box::new(AutoAnswerYes));
+        assert_eq!(chain.apply(b"some prompt"), b"some prompty\n");
+    }
+
+    #[test]
+    fn test_filters_apply_in_registration_order() {
+        let mut chain = FilterChain::new();
+        chain.register(Box::new(Uppercase));
+        chain.register(Box::new(AutoAnswerYes));
+        assert_eq!(chain.apply(b"hello"), b"HELLOy\n");
+    }
+}
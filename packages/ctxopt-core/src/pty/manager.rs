@@ -6,9 +6,13 @@ use portable_pty::{
     native_pty_system, Child, CommandBuilder, MasterPty, PtySize as PortablePtySize,
 };
 use std::io::{Read, Write};
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::Mutex;
+use tokio_stream::{Stream, StreamExt};
 
 // Unix-specific imports for raw mode
 #[cfg(unix)]
@@ -109,6 +113,31 @@ pub fn enter_raw_mode() -> Result<(), PtyError> {
     Ok(())
 }
 
+/// Interroge la taille du terminal contrôlant via `TIOCGWINSZ` sur stdin
+///
+/// Retourne `None` si stdin n'est pas un terminal ou que l'ioctl échoue
+/// (ex: process lancé en tâche de fond sans tty).
+#[cfg(unix)]
+pub fn query_controlling_terminal_size() -> Option<PtySize> {
+    // SAFETY: `winsize` est un type POD et `ioctl` ne touche qu'à ce buffer.
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::ioctl(libc::STDIN_FILENO, libc::TIOCGWINSZ, &mut size) };
+    if ret == 0 && size.ws_row > 0 && size.ws_col > 0 {
+        Some(PtySize {
+            rows: size.ws_row,
+            cols: size.ws_col,
+        })
+    } else {
+        None
+    }
+}
+
+/// Toujours `None` sur les plateformes non-Unix (pas de `TIOCGWINSZ`)
+#[cfg(not(unix))]
+pub fn query_controlling_terminal_size() -> Option<PtySize> {
+    None
+}
+
 /// Taille du PTY en lignes/colonnes
 #[derive(Debug, Clone, Copy)]
 pub struct PtySize {
@@ -141,11 +170,24 @@ pub struct PtyManager {
     /// Writer pour envoyer des données au PTY
     writer: Arc<Mutex<Box<dyn Write + Send>>>,
 
-    /// Channel receiver pour les données lues du PTY
-    read_rx: Arc<Mutex<tokio::sync::mpsc::Receiver<Vec<u8>>>>,
+    /// Channel receiver pour les données lues du PTY (ou l'erreur d'IO qui a
+    /// mis fin à la lecture)
+    read_rx: Arc<Mutex<tokio::sync::mpsc::Receiver<Result<Vec<u8>, PtyError>>>>,
 
     /// Child process (Claude Code)
     child: Arc<Mutex<Box<dyn Child + Send + Sync>>>,
+
+    /// Tâche de fond qui réagit à SIGWINCH pour propager la taille du
+    /// terminal hôte au PTY (`None` sur les plateformes non-Unix)
+    resize_watcher: Option<tokio::task::JoinHandle<()>>,
+
+    /// Commande et arguments d'origine, conservés pour [`Self::respawn`]
+    /// (mode watch, voir `pty::watch`)
+    command: String,
+    args: Vec<String>,
+
+    /// Dernière taille de PTY connue, réutilisée par [`Self::respawn`]
+    size: PtySize,
 }
 
 impl PtyManager {
@@ -203,9 +245,10 @@ impl PtyManager {
             .map_err(|e| PtyError::CreateError(e.to_string()))?;
 
         // Créer un channel pour la communication avec le thread de lecture
-        let (read_tx, read_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(100);
+        let (read_tx, read_rx) = tokio::sync::mpsc::channel::<Result<Vec<u8>, PtyError>>(100);
 
-        // Spawner un thread dédié pour la lecture du PTY
+        // Spawner un thread dédié pour la lecture du PTY (le reader de
+        // `portable_pty` est bloquant, impossible de l'awaiter directement)
         std::thread::spawn(move || {
             let mut buffer = vec![0u8; 4096];
             loop {
@@ -217,53 +260,113 @@ impl PtyManager {
                     Ok(n) => {
                         let data = buffer[..n].to_vec();
                         // Envoyer les données via le channel (ignore si le receiver est fermé)
-                        if read_tx.blocking_send(data).is_err() {
+                        if read_tx.blocking_send(Ok(data)).is_err() {
                             break;
                         }
                     }
                     Err(e) => {
-                        // Erreur de lecture - logger et continuer ou break selon le type
-                        if e.kind() != std::io::ErrorKind::Interrupted {
-                            break;
+                        if e.kind() == std::io::ErrorKind::Interrupted {
+                            continue;
                         }
+                        // Erreur réelle: on la propage au stream pour que
+                        // l'appelant sache que la lecture s'est arrêtée anormalement
+                        let _ = read_tx.blocking_send(Err(PtyError::IoError(e)));
+                        break;
                     }
                 }
             }
         });
 
+        let master = Arc::new(Mutex::new(pair.master));
+        let resize_watcher = Self::spawn_resize_watcher(Arc::clone(&master));
+
         Ok(Self {
-            master: Arc::new(Mutex::new(pair.master)),
+            master,
             writer: Arc::new(Mutex::new(writer)),
             read_rx: Arc::new(Mutex::new(read_rx)),
             child: Arc::new(Mutex::new(child)),
+            resize_watcher,
+            command: command.to_string(),
+            args: args.iter().map(|a| (*a).to_string()).collect(),
+            size,
         })
     }
 
+    /// Démarre la tâche d'écoute de SIGWINCH qui répercute la taille du
+    /// terminal hôte sur le PTY, pour que le child reçoive son propre
+    /// SIGWINCH avec les bonnes dimensions (comportement standard des
+    /// wrappers de PTY comme tmux/screen).
+    ///
+    /// Les rafales de signaux arrivant dans les ~50ms sont coalescées pour
+    /// n'émettre qu'un seul resize. No-op sur les plateformes non-Unix.
+    #[cfg(unix)]
+    fn spawn_resize_watcher(
+        master: Arc<Mutex<Box<dyn MasterPty + Send>>>,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut winch = signal(SignalKind::window_change()).ok()?;
+
+        Some(tokio::spawn(async move {
+            while winch.recv().await.is_some() {
+                // Coalesce les signaux qui arrivent en rafale
+                while tokio::time::timeout(std::time::Duration::from_millis(50), winch.recv())
+                    .await
+                    .is_ok()
+                {
+                    // Un autre SIGWINCH est arrivé pendant la fenêtre de debounce, on continue à drainer
+                }
+
+                if let Some(new_size) = query_controlling_terminal_size() {
+                    let master = master.lock().await;
+                    let _ = master.resize(new_size.into());
+                }
+            }
+        }))
+    }
+
+    /// No-op sur les plateformes non-Unix: pas de SIGWINCH
+    #[cfg(not(unix))]
+    fn spawn_resize_watcher(
+        _master: Arc<Mutex<Box<dyn MasterPty + Send>>>,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        None
+    }
+
+    /// Expose la sortie du PTY comme un `Stream` annulable et avec
+    /// backpressure naturelle, plutôt qu'un polling à intervalle fixe.
+    ///
+    /// Le thread de lecture reste nécessaire (le reader de `portable_pty`
+    /// est synchrone), mais chaque item du stream porte désormais le
+    /// résultat de la lecture: `Err` si le PTY a rencontré une erreur d'IO
+    /// réelle, au lieu de la laisser disparaître silencieusement.
+    pub fn output(&self) -> impl Stream<Item = Result<Vec<u8>, PtyError>> + '_ {
+        PtyOutputStream {
+            read_rx: Arc::clone(&self.read_rx),
+        }
+    }
+
     /// Lecture asynchrone avec timeout court
     ///
-    /// Attend les données pendant un court délai puis retourne.
+    /// Wrapper de compatibilité fin au-dessus de [`Self::output`]: draine
+    /// d'abord tout ce qui est immédiatement disponible, puis attend un
+    /// court instant (max 10ms) pour de nouvelles données.
     pub async fn read_async(&self) -> Result<Vec<u8>, PtyError> {
-        let mut rx = self.read_rx.lock().await;
-
-        // Collecter toutes les données disponibles avec un petit timeout
+        let mut stream = self.output();
         let mut all_data = Vec::new();
 
-        // D'abord, récupérer tout ce qui est déjà disponible
-        while let Ok(data) = rx.try_recv() {
-            all_data.extend(data);
+        // D'abord, récupérer tout ce qui est déjà disponible sans attendre
+        while let Ok(Some(item)) = tokio::time::timeout(Duration::ZERO, stream.next()).await {
+            all_data.extend(item?);
         }
 
-        // Si on a déjà des données, les retourner immédiatement
         if !all_data.is_empty() {
             return Ok(all_data);
         }
 
         // Sinon, attendre un peu pour de nouvelles données (max 10ms)
-        match tokio::time::timeout(
-            std::time::Duration::from_millis(10),
-            rx.recv()
-        ).await {
-            Ok(Some(data)) => Ok(data),
+        match tokio::time::timeout(Duration::from_millis(10), stream.next()).await {
+            Ok(Some(item)) => item,
             Ok(None) => Ok(Vec::new()), // Channel fermé
             Err(_) => Ok(Vec::new()),   // Timeout
         }
@@ -319,6 +422,191 @@ impl PtyManager {
             .map_err(|e| PtyError::SpawnError(e.to_string()))?;
         Ok(())
     }
+
+    /// Tue le child courant et le relance avec la même commande/taille, pour
+    /// le mode watch (voir `pty::watch::spawn_watcher`)
+    ///
+    /// Recrée entièrement le master/writer/reader/thread de lecture (comme
+    /// [`Self::new`]); seuls la commande, les arguments et la taille du PTY
+    /// sont conservés de l'instance précédente. Un `PtyManager` splitté (voir
+    /// [`Self::split`]) ne peut pas être respawné: ses moitiés n'ont plus
+    /// accès à ce champ `self`.
+    pub async fn respawn(&mut self) -> Result<(), PtyError> {
+        self.kill().await.ok();
+
+        let args: Vec<&str> = self.args.iter().map(String::as_str).collect();
+        let mut fresh = Self::new(&self.command, &args, self.size)?;
+
+        if let Some(handle) = self.resize_watcher.take() {
+            handle.abort();
+        }
+
+        self.master = Arc::clone(&fresh.master);
+        self.writer = Arc::clone(&fresh.writer);
+        self.read_rx = Arc::clone(&fresh.read_rx);
+        self.child = Arc::clone(&fresh.child);
+        self.resize_watcher = fresh.resize_watcher.take();
+
+        Ok(())
+    }
+
+    /// Sépare le PTY en une moitié lecture et une moitié écriture qui ne se
+    /// partagent plus aucun verrou: une tâche peut consommer `PtyReader`
+    /// pendant qu'une autre pilote `PtyWriter`, sans contention mutuelle.
+    ///
+    /// La tâche de fond SIGWINCH (liée à `master`) est transférée à
+    /// `PtyWriter`, qui porte donc aussi `resize`/`kill`.
+    pub fn split(mut self) -> (PtyReader, PtyWriter) {
+        let resize_watcher = self.resize_watcher.take();
+
+        let reader = PtyReader {
+            read_rx: Arc::clone(&self.read_rx),
+        };
+        let writer = PtyWriter {
+            master: Arc::clone(&self.master),
+            writer: Arc::clone(&self.writer),
+            child: Arc::clone(&self.child),
+            resize_watcher,
+        };
+
+        (reader, writer)
+    }
+}
+
+/// Moitié lecture d'un `PtyManager` splitté, obtenue via [`PtyManager::split`]
+pub struct PtyReader {
+    read_rx: Arc<Mutex<tokio::sync::mpsc::Receiver<Result<Vec<u8>, PtyError>>>>,
+}
+
+impl PtyReader {
+    /// Expose la sortie du PTY comme un `Stream`, voir [`PtyManager::output`]
+    pub fn output(&self) -> impl Stream<Item = Result<Vec<u8>, PtyError>> + '_ {
+        PtyOutputStream {
+            read_rx: Arc::clone(&self.read_rx),
+        }
+    }
+
+    /// Voir [`PtyManager::read_async`]
+    pub async fn read_async(&self) -> Result<Vec<u8>, PtyError> {
+        let mut stream = self.output();
+        let mut all_data = Vec::new();
+
+        while let Ok(Some(item)) = tokio::time::timeout(Duration::ZERO, stream.next()).await {
+            all_data.extend(item?);
+        }
+
+        if !all_data.is_empty() {
+            return Ok(all_data);
+        }
+
+        match tokio::time::timeout(Duration::from_millis(10), stream.next()).await {
+            Ok(Some(item)) => item,
+            Ok(None) => Ok(Vec::new()),
+            Err(_) => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Moitié écriture (+ contrôle) d'un `PtyManager` splitté, obtenue via
+/// [`PtyManager::split`]
+pub struct PtyWriter {
+    master: Arc<Mutex<Box<dyn MasterPty + Send>>>,
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    child: Arc<Mutex<Box<dyn Child + Send + Sync>>>,
+    resize_watcher: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl PtyWriter {
+    /// Voir [`PtyManager::write`]
+    pub async fn write(&self, data: &[u8]) -> Result<(), PtyError> {
+        let mut writer = self.writer.lock().await;
+        writer
+            .write_all(data)
+            .map_err(|e| PtyError::WriteError(e.to_string()))?;
+        writer
+            .flush()
+            .map_err(|e| PtyError::WriteError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Voir [`PtyManager::write_str`]
+    pub async fn write_str(&self, data: &str) -> Result<(), PtyError> {
+        self.write(data.as_bytes()).await
+    }
+
+    /// Voir [`PtyManager::is_running`]
+    pub async fn is_running(&self) -> bool {
+        let mut child = self.child.lock().await;
+        matches!(child.try_wait(), Ok(None))
+    }
+
+    /// Voir [`PtyManager::wait`]
+    pub async fn wait(&self) -> Result<u32, PtyError> {
+        let mut child = self.child.lock().await;
+        let status = child
+            .wait()
+            .map_err(|e| PtyError::SpawnError(e.to_string()))?;
+        Ok(status.exit_code())
+    }
+
+    /// Voir [`PtyManager::resize`]
+    pub async fn resize(&self, new_size: PtySize) -> Result<(), PtyError> {
+        let master = self.master.lock().await;
+        master
+            .resize(new_size.into())
+            .map_err(|e| PtyError::CreateError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Voir [`PtyManager::kill`]
+    pub async fn kill(&self) -> Result<(), PtyError> {
+        let mut child = self.child.lock().await;
+        child
+            .kill()
+            .map_err(|e| PtyError::SpawnError(e.to_string()))?;
+        Ok(())
+    }
+}
+
+impl Drop for PtyWriter {
+    fn drop(&mut self) {
+        if let Some(handle) = self.resize_watcher.take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Stream adaptant le channel de lecture du PTY sans `async-stream`
+///
+/// `Mutex::poll_recv` a besoin d'un verrou; comme plusieurs clones du
+/// stream peuvent en théorie être pollés en parallèle, un verrou pris on
+/// redonne juste la main au runtime (`Poll::Pending` + re-wake) plutôt que
+/// de bloquer.
+struct PtyOutputStream {
+    read_rx: Arc<Mutex<tokio::sync::mpsc::Receiver<Result<Vec<u8>, PtyError>>>>,
+}
+
+impl Stream for PtyOutputStream {
+    type Item = Result<Vec<u8>, PtyError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.read_rx.try_lock() {
+            Ok(mut guard) => guard.poll_recv(cx),
+            Err(_) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for PtyManager {
+    fn drop(&mut self) {
+        // Évite de laisser tourner la tâche SIGWINCH après la fin du PTY
+        if let Some(handle) = self.resize_watcher.take() {
+            handle.abort();
+        }
+    }
 }
 
 // Tests unitaires
@@ -397,4 +685,67 @@ mod tests {
 
         assert!(output_str.contains("async test"));
     }
+
+    #[tokio::test]
+    async fn test_pty_output_stream() {
+        let pty = PtyManager::new("echo", &["via stream"], PtySize::default())
+            .expect("Failed to create PTY");
+
+        let mut stream = pty.output();
+        let mut collected = Vec::new();
+
+        // echo termine vite: on draine jusqu'à fermeture du channel ou timeout global
+        loop {
+            match tokio::time::timeout(Duration::from_millis(200), stream.next()).await {
+                Ok(Some(Ok(chunk))) => collected.extend(chunk),
+                Ok(Some(Err(e))) => panic!("unexpected IO error: {e}"),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        let text = String::from_utf8_lossy(&collected);
+        assert!(text.contains("via stream"));
+    }
+
+    #[tokio::test]
+    async fn test_pty_split_read_and_write_independently() {
+        let pty = PtyManager::new("cat", &[], PtySize::default()).expect("Failed to create PTY");
+        let (reader, writer) = pty.split();
+
+        writer
+            .write_str("split test\n")
+            .await
+            .expect("Failed to write");
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        let output = reader.read_async().await.expect("Failed to read");
+        let output_str = String::from_utf8_lossy(&output);
+
+        assert!(output_str.contains("split test"));
+
+        writer.kill().await.ok();
+    }
+
+    #[test]
+    fn test_query_controlling_terminal_size_does_not_panic() {
+        // Pas de tty en CI: on vérifie juste que l'ioctl ne panique pas et
+        // que les tailles retournées, si `Some`, sont cohérentes.
+        if let Some(size) = query_controlling_terminal_size() {
+            assert!(size.rows > 0);
+            assert!(size.cols > 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resize_watcher_spawned_on_unix() {
+        let pty =
+            PtyManager::new("sleep", &["1"], PtySize::default()).expect("Failed to create PTY");
+
+        #[cfg(unix)]
+        assert!(pty.resize_watcher.is_some());
+        #[cfg(not(unix))]
+        assert!(pty.resize_watcher.is_none());
+
+        pty.kill().await.ok();
+    }
 }
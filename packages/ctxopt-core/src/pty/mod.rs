@@ -3,13 +3,17 @@
 //! Ce module gère la création et manipulation des pseudo-terminaux
 //! cross-platform (Unix PTY, Windows ConPTY).
 
+pub(crate) mod filter;
 pub(crate) mod manager;
+pub(crate) mod watch;
 
 // enter_raw_mode doit rester pub car utilisé dans lib.rs #[napi]
 pub use manager::enter_raw_mode;
-pub(crate) use manager::{PtyManager, PtySize};
-#[cfg(test)]
-pub(crate) use manager::PtyError;
+pub(crate) use filter::{FilterAction, FilterChain, InputFilter};
+pub(crate) use manager::{
+    query_controlling_terminal_size, PtyError, PtyManager, PtyReader, PtySize, PtyWriter,
+};
+pub(crate) use watch::{spawn_watcher, WatchConfig, WatchError, WatchHandle};
 
 #[cfg(unix)]
 pub(crate) use manager::RawModeGuard;
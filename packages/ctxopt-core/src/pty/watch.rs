@@ -0,0 +1,166 @@
+//! Surveillance de fichiers pour relancer la commande wrappée à chaque
+//! changement (mode watch)
+//!
+//! `PtyManager` sait spawner, lire, redimensionner et attendre un child, mais
+//! rien ne garde une commande de build/test en cours en la relançant à
+//! chaque modification du code source - exactement la boucle où les
+//! suggestions de compression d'erreurs de ctxopt comptent le plus. Ce
+//! module reprend l'approche du test runner `--watch` de Deno: les chemins
+//! surveillés sont collectés, les rafales d'évènements (un `cargo build`
+//! touche souvent plusieurs fichiers d'un coup) sont coalescées sur une
+//! fenêtre de debounce configurable, puis un seul signal de restart est émis
+//! - même stratégie de coalescence que `PtyManager::spawn_resize_watcher`
+//! pour SIGWINCH, appliquée ici aux évènements `notify`.
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+/// Erreurs de mise en place de la surveillance de fichiers
+#[derive(Error, Debug)]
+pub enum WatchError {
+    #[error("Failed to watch path {path}: {source}")]
+    WatchSetupError {
+        path: PathBuf,
+        source: notify::Error,
+    },
+}
+
+/// Configuration du mode watch
+#[derive(Debug, Clone)]
+pub struct WatchConfig {
+    /// Racines surveillées (fichiers ou dossiers, récursif)
+    pub paths: Vec<PathBuf>,
+
+    /// Fenêtre de coalescence: une rafale d'évènements qui arrivent dans cet
+    /// intervalle ne déclenche qu'un seul restart
+    pub debounce: Duration,
+}
+
+impl Default for WatchConfig {
+    /// 300ms, assez large pour absorber les écritures séquentielles d'un
+    /// `cargo build`/`tsc` sur plusieurs fichiers sans retarder sensiblement
+    /// le restart perçu par l'utilisateur
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            debounce: Duration::from_millis(300),
+        }
+    }
+}
+
+/// Poignée vers la tâche de fond qui surveille les fichiers
+///
+/// Le drop arrête la tâche de coalescence; le `notify::Watcher` sous-jacent
+/// est droppé avec elle, ce qui désenregistre la surveillance du système de
+/// fichiers.
+pub struct WatchHandle {
+    _watcher: RecommendedWatcher,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Démarre la surveillance de `config.paths` et renvoie un récepteur qui
+/// reçoit le nombre d'évènements coalescés à chaque fenêtre de debounce
+/// écoulée (un restart par item reçu)
+///
+/// Volontairement découplé de `PtyManager`/`StreamAnalyzer`: ce module ne
+/// fait que produire un flux de "quelque chose a changé", à charge de
+/// l'appelant (voir `CtxOptSession::poll_watch`) de tuer/relancer le child
+/// et de nourrir l'analyseur avec la sortie fraîche.
+pub fn spawn_watcher(config: &WatchConfig) -> Result<(WatchHandle, mpsc::Receiver<usize>), WatchError> {
+    let (raw_tx, mut raw_rx) = mpsc::unbounded_channel::<Event>();
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        if let Ok(event) = res {
+            // Le receiver ne ferme jamais tant que `WatchHandle` est vivant;
+            // une erreur ici ne peut venir que de la tâche déjà abortée.
+            let _ = raw_tx.send(event);
+        }
+    })
+    .map_err(|source| WatchError::WatchSetupError {
+        path: PathBuf::new(),
+        source,
+    })?;
+
+    for path in &config.paths {
+        watcher
+            .watch(path, RecursiveMode::Recursive)
+            .map_err(|source| WatchError::WatchSetupError {
+                path: path.clone(),
+                source,
+            })?;
+    }
+
+    let (restart_tx, restart_rx) = mpsc::channel(1);
+    let debounce = config.debounce;
+
+    let task = tokio::spawn(async move {
+        while raw_rx.recv().await.is_some() {
+            let mut coalesced = 1usize;
+
+            // Draine les évènements qui arrivent en rafale pendant la
+            // fenêtre de debounce, sans en émettre un restart par fichier
+            while tokio::time::timeout(debounce, raw_rx.recv()).await.is_ok() {
+                coalesced += 1;
+            }
+
+            if restart_tx.send(coalesced).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok((
+        WatchHandle {
+            _watcher: watcher,
+            task,
+        },
+        restart_rx,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_config_default_debounce() {
+        let config = WatchConfig::default();
+        assert_eq!(config.debounce, Duration::from_millis(300));
+        assert!(config.paths.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_spawn_watcher_coalesces_rapid_changes() {
+        let dir = std::env::temp_dir().join(format!("ctxopt-watch-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("failed to create temp dir");
+
+        let config = WatchConfig {
+            paths: vec![dir.clone()],
+            debounce: Duration::from_millis(50),
+        };
+
+        let (_handle, mut restart_rx) = spawn_watcher(&config).expect("failed to spawn watcher");
+
+        for i in 0..5 {
+            std::fs::write(dir.join(format!("file_{i}.txt")), b"changed").ok();
+        }
+
+        let coalesced = tokio::time::timeout(Duration::from_secs(2), restart_rx.recv())
+            .await
+            .expect("timed out waiting for a restart signal")
+            .expect("watcher channel closed unexpectedly");
+
+        assert!(coalesced >= 1);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -4,8 +4,14 @@
 //! les opportunités d'optimisation de tokens.
 
 use super::buffer::RingBuffer;
+use super::custom::{CustomDetector, CustomPatternError};
+use super::dedup::{self, DEFAULT_SIMILARITY_THRESHOLD};
+use super::events::AnalysisEvent;
 use super::patterns::{BuildTool, ContentType, PATTERNS};
+use super::screen::TerminalScreen;
+use super::stats::SessionStats;
 use crate::tokens::TokenEstimator;
+use std::collections::HashMap;
 
 /// Seuil pour détecter un output volumineux (en caractères)
 const LARGE_OUTPUT_THRESHOLD: usize = 5000;
@@ -13,6 +19,27 @@ const LARGE_OUTPUT_THRESHOLD: usize = 5000;
 /// Capacité du ring buffer (caractères)
 const BUFFER_CAPACITY: usize = 50000;
 
+/// Taille par défaut (en caractères) de la fenêtre glissante scannée pour
+/// `detect_build_errors`/`detect_file_read`. Assez large pour recoller un
+/// message d'erreur ou une ligne `Reading file:` coupée entre deux chunks,
+/// ou qui aurait scrollé hors de l'écran visible avant d'être scannée.
+const DEFAULT_DETECTION_WINDOW: usize = 4000;
+
+/// Compteurs agrégés pour un type de contenu (clé: `ContentType::kind()`)
+///
+/// Suit le modèle d'agrégation par bin du `CodeStats` de rustc: `occurrences`
+/// compte le nombre de fois où ce type a été détecté, `tokens` la somme des
+/// `token_estimate` des chunks où il est apparu. Alimente
+/// `CtxOptSession::stats_breakdown`, combiné aux compteurs d'injection de
+/// `ContextInjector` (voir `injector::triggers`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContentTypeCounters {
+    /// Nombre de détections de ce type depuis le dernier `reset`
+    pub occurrences: usize,
+    /// Somme des `token_estimate` des chunks où ce type est apparu
+    pub tokens: usize,
+}
+
 /// Résultat d'analyse d'un chunk
 #[derive(Debug, Clone)]
 pub struct AnalysisResult {
@@ -25,13 +52,24 @@ pub struct AnalysisResult {
     /// Taille totale accumulée
     pub total_size: usize,
 
-    /// Texte nettoyé (sans ANSI)
+    /// Texte nettoyé (sans ANSI), avec les runs de lignes quasi-identiques
+    /// fusionnés sous une seule représentante `(×N)`
     pub clean_text: String,
+
+    /// Nombre de lignes économisées par la fusion des quasi-doublons
+    pub lines_saved: usize,
+
+    /// Tokens économisés par la fusion des quasi-doublons (estimation
+    /// avant/après compaction)
+    pub tokens_saved: usize,
 }
 
 /// Analyseur de flux stdout
 pub struct StreamAnalyzer {
-    /// Buffer pour l'historique
+    /// Modèle d'écran terminal (vt100), source du texte rendu et stable
+    screen: TerminalScreen,
+
+    /// Buffer pour l'historique du texte rendu
     buffer: RingBuffer,
 
     /// Estimateur de tokens
@@ -42,44 +80,164 @@ pub struct StreamAnalyzer {
 
     /// Compteur d'erreurs détectées
     error_count: usize,
+
+    /// Nombre total de caractères rendus depuis le dernier reset (position
+    /// absolue et monotone, ne décroît jamais)
+    total_chars_rendered: usize,
+
+    /// Position absolue (en caractères) jusqu'à laquelle les matches ont
+    /// déjà été comptés, pour ne pas recompter un pattern déjà vu dans la
+    /// fenêtre glissante d'un appel précédent
+    last_scanned_offset: usize,
+
+    /// Taille de la fenêtre glissante (en caractères) scannée par
+    /// `detect_build_errors`/`detect_file_read`
+    detection_window: usize,
+
+    /// Dernier texte rendu par l'écran virtuel, pour ne pousser dans le
+    /// ring buffer que le delta (le texte rendu est l'écran complet, pas
+    /// juste le chunk courant)
+    last_rendered_text: String,
+
+    /// Seuil de similarité au-delà duquel deux lignes consécutives sont
+    /// fusionnées dans `clean_text`
+    dedup_threshold: f64,
+
+    /// Détecteurs enregistrés au runtime, évalués après les détecteurs natifs
+    /// dans leur ordre d'enregistrement (voir `stream::custom`)
+    custom_detectors: Vec<CustomDetector>,
+
+    /// Compteur d'erreurs cumulé par outil (clé: `BuildTool::as_str()`),
+    /// alimenté pour le flux d'évènements sérialisables (`analyze_with_event`)
+    tool_error_counts: HashMap<String, usize>,
+
+    /// Compteurs agrégés par type de contenu (clé: `ContentType::kind()`),
+    /// pour `stats_breakdown` (voir `ContentTypeCounters`)
+    content_type_stats: HashMap<String, ContentTypeCounters>,
+
+    /// Statistiques robustes (distributions taille/tokens, erreurs, suggestions)
+    /// accumulées sur toute la durée de vie de l'analyseur, voir `SessionStats`
+    session_stats: SessionStats,
 }
 
 impl StreamAnalyzer {
     /// Crée un nouvel analyseur
     pub fn new() -> Self {
         Self {
+            screen: TerminalScreen::new(),
             buffer: RingBuffer::new(BUFFER_CAPACITY),
             token_estimator: TokenEstimator::new(),
             total_tokens: 0,
             error_count: 0,
+            total_chars_rendered: 0,
+            last_scanned_offset: 0,
+            detection_window: DEFAULT_DETECTION_WINDOW,
+            last_rendered_text: String::new(),
+            dedup_threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            custom_detectors: Vec::new(),
+            tool_error_counts: HashMap::new(),
+            content_type_stats: HashMap::new(),
+            session_stats: SessionStats::new(),
         }
     }
 
-    /// Analyse un chunk de données et retourne les types détectés
-    pub fn analyze(&mut self, chunk: &str) -> AnalysisResult {
-        // Nettoyer les ANSI escape codes
-        let clean_text = self.strip_ansi(chunk);
+    /// Règle la taille de la fenêtre glissante scannée pour la détection
+    /// (utile pour les tests ou pour ajuster le compromis coût/couverture)
+    #[allow(dead_code)]
+    pub fn with_detection_window(mut self, window: usize) -> Self {
+        self.detection_window = window;
+        self
+    }
 
-        // Ajouter au buffer
-        self.buffer.push(&clean_text);
+    /// Règle le seuil de similarité utilisé pour fusionner les lignes
+    /// quasi-identiques dans `clean_text` (défaut ~0.85)
+    #[allow(dead_code)]
+    pub fn with_dedup_threshold(mut self, threshold: f64) -> Self {
+        self.dedup_threshold = threshold;
+        self
+    }
 
-        // Estimer les tokens
-        let token_estimate = self.token_estimator.estimate(&clean_text);
+    /// Enregistre un détecteur supplémentaire (ex: Maven, Gradle, pytest,
+    /// cargo-nextest, ou un outil maison) évalué après les détecteurs natifs
+    ///
+    /// La regex est compilée une seule fois ici, pas à chaque `analyze`: le
+    /// chemin chaud ne fait que parcourir le `Vec` déjà prêt.
+    #[allow(dead_code)]
+    pub fn register_detector(
+        &mut self,
+        name: impl Into<String>,
+        pattern: &str,
+        label: impl Into<String>,
+    ) -> Result<(), CustomPatternError> {
+        self.custom_detectors
+            .push(CustomDetector::new(name, pattern, label)?);
+        Ok(())
+    }
+
+    /// Analyse un chunk de bytes bruts (avec ANSI) et retourne les types détectés
+    ///
+    /// Les bytes sont d'abord rejoués dans un écran virtuel `vt100`; toute la
+    /// détection qui suit travaille sur le texte rendu stable (`clean_text`),
+    /// pas sur les bytes bruts, ce qui survit aux redraws et aux séquences
+    /// d'échappement coupées entre deux chunks.
+    pub fn analyze(&mut self, chunk: &[u8]) -> AnalysisResult {
+        self.screen.process(chunk);
+        let clean_text = self.screen.visible_text();
+
+        // `clean_text` est l'écran entier rendu jusqu'ici, pas juste le
+        // chunk courant: on ne pousse dans le ring buffer que le delta par
+        // rapport au dernier rendu, sinon le buffer se remplirait de
+        // copies dupliquées de tout l'historique à chaque appel.
+        let delta = Self::rendered_delta(&self.last_rendered_text, &clean_text);
+        self.buffer.push(&delta);
+        self.total_chars_rendered += delta.chars().count();
+        self.last_rendered_text = clean_text;
+
+        // Fusionner les runs de lignes quasi-identiques avant d'estimer les
+        // tokens: c'est le texte compacté qui est réellement utile à
+        // transmettre, le texte brut ne sert qu'à mesurer l'économie. On
+        // travaille sur `delta` (ce que ce chunk vient d'ajouter à l'écran),
+        // pas sur `clean_text` (l'écran entier rendu depuis le début), sinon
+        // `token_estimate` grossirait en O(n²) au fil du remplissage de
+        // l'écran au lieu de rester un coût par chunk.
+        let original_estimate = self.token_estimator.estimate(&delta);
+        let compacted = dedup::compact_lines(&delta, self.dedup_threshold);
+        let token_estimate = self.token_estimator.estimate(&compacted.text);
+        let tokens_saved = original_estimate.saturating_sub(token_estimate);
         self.total_tokens += token_estimate;
 
-        // Détecter les patterns
+        // Détecter les patterns sur une fenêtre glissante du ring buffer
+        // (pas sur `clean_text` seul) pour recoller les messages coupés
+        // entre deux chunks ou qui ont scrollé hors de l'écran visible.
+        let window = self.buffer.last_n(self.detection_window);
+        let window_start = self.total_chars_rendered.saturating_sub(window.chars().count());
+
         let mut content_types = Vec::new();
 
         // 1. Détecter les erreurs de build
-        if let Some(build_error) = self.detect_build_errors(&clean_text) {
+        if let Some(build_error) = self.detect_build_errors(&window, window_start) {
             content_types.push(build_error);
         }
 
         // 2. Détecter les lectures de fichiers
-        if let Some(file_read) = self.detect_file_read(&clean_text) {
+        if let Some(file_read) = self.detect_file_read(&window, window_start) {
             content_types.push(file_read);
         }
 
+        // 2bis. Détecteurs personnalisés enregistrés au runtime, dans leur
+        // ordre d'enregistrement, après les détecteurs natifs
+        for detector in &self.custom_detectors {
+            let count = self.count_new_matches(detector.pattern(), &window, window_start);
+            if count > 0 {
+                content_types.push(ContentType::Custom {
+                    label: detector.label().to_string(),
+                    count,
+                });
+            }
+        }
+
+        self.last_scanned_offset = self.total_chars_rendered;
+
         // 3. Détecter les outputs volumineux
         if self.buffer.len() > LARGE_OUTPUT_THRESHOLD {
             content_types.push(ContentType::LargeOutput {
@@ -87,11 +245,15 @@ impl StreamAnalyzer {
             });
         }
 
-        // 4. Détecter le prompt ready
-        if self.detect_prompt_ready(&clean_text) {
+        // 4. Détecter le prompt ready (ligne sous le curseur, pas une regex de fin de flux)
+        if self.detect_prompt_ready() {
             content_types.push(ContentType::PromptReady);
-            // Reset le buffer après un prompt
+            // Reset le buffer et l'écran après un prompt
             self.buffer.clear();
+            self.screen.reset();
+            self.last_rendered_text.clear();
+            self.total_chars_rendered = 0;
+            self.last_scanned_offset = 0;
         }
 
         // Si aucun pattern détecté
@@ -99,23 +261,102 @@ impl StreamAnalyzer {
             content_types.push(ContentType::Normal);
         }
 
+        for content_type in &content_types {
+            if let ContentType::BuildError { error_count, tool } = content_type {
+                *self.tool_error_counts.entry(tool.as_str().to_string()).or_insert(0) += error_count;
+                self.session_stats.record_build_errors(tool.as_str(), *error_count);
+            }
+
+            let counters = self.content_type_stats.entry(content_type.kind()).or_default();
+            counters.occurrences += 1;
+            counters.tokens += token_estimate;
+        }
+
+        self.session_stats.record_chunk(chunk.len(), token_estimate);
+
         AnalysisResult {
             content_types,
             token_estimate,
             total_size: self.buffer.len(),
-            clean_text,
+            clean_text: compacted.text,
+            lines_saved: compacted.lines_saved,
+            tokens_saved,
+        }
+    }
+
+    /// Comme `analyze`, mais renvoie en plus un `AnalysisEvent` sérialisable
+    /// (JSON newline-delimited) pour un sink de métriques externe ou un
+    /// rejeu déterministe dans les tests. Opt-in: `analyze` seul ne paie pas
+    /// le coût de construction de l'évènement.
+    #[allow(dead_code)]
+    pub fn analyze_with_event(&mut self, chunk: &[u8]) -> (AnalysisResult, AnalysisEvent) {
+        let result = self.analyze(chunk);
+        let event = AnalysisEvent::from_result(
+            &result,
+            self.total_tokens,
+            self.error_count,
+            self.tool_error_counts.clone(),
+        );
+        (result, event)
+    }
+
+    /// Calcule le texte ajouté à l'écran par le chunk qui vient d'être traité
+    ///
+    /// `new` est le rendu complet de l'écran vt100 jusqu'ici, pas juste le
+    /// chunk courant: sans scroll, `new` commence simplement par `old` et le
+    /// delta est le suffixe restant (`strip_prefix`). Mais dès que l'output
+    /// dépasse `SCREEN_ROWS` lignes (routine pour les sorties de build/test
+    /// visées par ctxopt), vt100 scrolle la grille: les lignes les plus
+    /// anciennes de `old` sortent du haut de l'écran et `new` ne commence
+    /// donc plus par `old`. Dans ce cas on cherche le plus long suffixe de
+    /// `old` qui apparaît comme préfixe de `new` (les lignes encore communes
+    /// aux deux rendus) et on ne retient comme delta que ce qui suit, au
+    /// lieu de repousser tout l'écran dans le ring buffer à chaque appel.
+    fn rendered_delta(old: &str, new: &str) -> String {
+        if let Some(delta) = new.strip_prefix(old) {
+            return delta.to_string();
         }
+
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+
+        // Du plus grand chevauchement (le moins de lignes perdues) au plus
+        // petit, pour retenir le delta le plus court qui explique le
+        // nouveau rendu.
+        for dropped in 0..old_lines.len() {
+            let shared = &old_lines[dropped..];
+            if new_lines.len() >= shared.len() && new_lines[..shared.len()] == *shared {
+                return new_lines[shared.len()..].join("\n");
+            }
+        }
+
+        // Aucun chevauchement trouvé (reset d'écran, ou sortie qui a
+        // entièrement remplacé l'historique connu en un seul chunk): pas
+        // d'autre choix que de considérer tout le rendu courant comme nouveau.
+        new.to_string()
     }
 
-    /// Supprime les codes ANSI escape
-    fn strip_ansi(&self, text: &str) -> String {
-        PATTERNS.ansi_escape.replace_all(text, "").to_string()
+    /// Compte, parmi les matches d'un pattern sur la fenêtre, ceux qui
+    /// n'ont pas déjà été comptés par un appel précédent (leur fin absolue
+    /// tombe après `last_scanned_offset`).
+    fn count_new_matches(&self, pattern: &regex::Regex, window: &str, window_start: usize) -> usize {
+        pattern
+            .find_iter(window)
+            .filter(|m| {
+                let abs_end = window_start + window[..m.end()].chars().count();
+                abs_end > self.last_scanned_offset
+            })
+            .count()
     }
 
-    /// Détecte les erreurs de build
-    fn detect_build_errors(&mut self, text: &str) -> Option<ContentType> {
+    /// Détecte les erreurs de build sur la fenêtre glissante
+    ///
+    /// Ne compte dans `error_count` que les matches pas encore vus (évite
+    /// de recompter un même message présent dans plusieurs fenêtres
+    /// successives).
+    fn detect_build_errors(&mut self, window: &str, window_start: usize) -> Option<ContentType> {
         // TypeScript
-        let ts_count = PATTERNS.typescript_error.find_iter(text).count();
+        let ts_count = self.count_new_matches(&PATTERNS.typescript_error, window, window_start);
         if ts_count > 0 {
             self.error_count += ts_count;
             return Some(ContentType::BuildError {
@@ -125,7 +366,7 @@ impl StreamAnalyzer {
         }
 
         // ESLint
-        let eslint_count = PATTERNS.eslint_error.find_iter(text).count();
+        let eslint_count = self.count_new_matches(&PATTERNS.eslint_error, window, window_start);
         if eslint_count > 0 {
             self.error_count += eslint_count;
             return Some(ContentType::BuildError {
@@ -135,7 +376,7 @@ impl StreamAnalyzer {
         }
 
         // Rust
-        let rust_count = PATTERNS.rust_error.find_iter(text).count();
+        let rust_count = self.count_new_matches(&PATTERNS.rust_error, window, window_start);
         if rust_count > 0 {
             self.error_count += rust_count;
             return Some(ContentType::BuildError {
@@ -145,7 +386,7 @@ impl StreamAnalyzer {
         }
 
         // Go
-        let go_count = PATTERNS.go_error.find_iter(text).count();
+        let go_count = self.count_new_matches(&PATTERNS.go_error, window, window_start);
         if go_count > 0 {
             self.error_count += go_count;
             return Some(ContentType::BuildError {
@@ -155,7 +396,7 @@ impl StreamAnalyzer {
         }
 
         // Python
-        let python_count = PATTERNS.python_error.find_iter(text).count();
+        let python_count = self.count_new_matches(&PATTERNS.python_error, window, window_start);
         if python_count > 0 {
             self.error_count += python_count;
             return Some(ContentType::BuildError {
@@ -165,7 +406,7 @@ impl StreamAnalyzer {
         }
 
         // Générique (dernière priorité)
-        let generic_count = PATTERNS.generic_error.find_iter(text).count();
+        let generic_count = self.count_new_matches(&PATTERNS.generic_error, window, window_start);
         if generic_count > 0 {
             self.error_count += generic_count;
             return Some(ContentType::BuildError {
@@ -177,9 +418,20 @@ impl StreamAnalyzer {
         None
     }
 
-    /// Détecte les lectures de fichiers
-    fn detect_file_read(&self, text: &str) -> Option<ContentType> {
-        if let Some(captures) = PATTERNS.file_read.captures(text) {
+    /// Détecte les lectures de fichiers sur la fenêtre glissante
+    ///
+    /// Ne retient que le premier match dont la fin absolue n'a pas déjà
+    /// été scannée, pour ne pas re-signaler la même lecture à chaque appel
+    /// tant qu'elle reste dans la fenêtre.
+    fn detect_file_read(&self, window: &str, window_start: usize) -> Option<ContentType> {
+        for captures in PATTERNS.file_read.captures_iter(window) {
+            let Some(whole_match) = captures.get(0) else {
+                continue;
+            };
+            let abs_end = window_start + window[..whole_match.end()].chars().count();
+            if abs_end <= self.last_scanned_offset {
+                continue;
+            }
             if let Some(file_match) = captures.get(4) {
                 return Some(ContentType::FileRead {
                     file_path: file_match.as_str().to_string(),
@@ -190,10 +442,12 @@ impl StreamAnalyzer {
     }
 
     /// Détecte si le prompt est prêt
-    fn detect_prompt_ready(&self, text: &str) -> bool {
-        // Vérifier les derniers caractères du buffer
-        let last_chars = self.buffer.last_n(50);
-        PATTERNS.prompt_ready.is_match(&last_chars) || PATTERNS.prompt_ready.is_match(text)
+    ///
+    /// Regarde la ligne sous le curseur de l'écran virtuel plutôt qu'une
+    /// regex de fin de flux: le curseur est toujours là où l'utilisateur
+    /// taperait, donc c'est fiable même quand le prompt est redessiné.
+    fn detect_prompt_ready(&self) -> bool {
+        PATTERNS.prompt_ready.is_match(&self.screen.cursor_row_text())
     }
 
     /// Retourne le total de tokens estimés
@@ -212,11 +466,44 @@ impl StreamAnalyzer {
         self.buffer.len()
     }
 
+    /// Retourne les N dernières lignes complètes du ring buffer, pour citer
+    /// le contexte qui a déclenché une détection (voir
+    /// `Suggestion::format_annotated`)
+    pub fn recent_lines(&self, n: usize) -> String {
+        self.buffer.last_n_lines(n)
+    }
+
+    /// Retourne les compteurs agrégés par type de contenu détecté depuis le
+    /// dernier `reset`, pour un dashboard "d'où viennent les tokens/suggestions"
+    pub fn content_type_stats(&self) -> &HashMap<String, ContentTypeCounters> {
+        &self.content_type_stats
+    }
+
+    /// Retourne les statistiques robustes (distributions taille/tokens,
+    /// erreurs, suggestions) accumulées depuis le dernier `reset`
+    pub fn session_stats(&self) -> &SessionStats {
+        &self.session_stats
+    }
+
+    /// Retourne les statistiques robustes en écriture, pour que l'appelant
+    /// (voir `SharedAnalyzer::record_suggestion`) y enregistre les
+    /// suggestions émises par `ContextInjector`
+    pub fn session_stats_mut(&mut self) -> &mut SessionStats {
+        &mut self.session_stats
+    }
+
     /// Reset les compteurs
     pub fn reset(&mut self) {
         self.buffer.clear();
+        self.screen.reset();
         self.total_tokens = 0;
         self.error_count = 0;
+        self.total_chars_rendered = 0;
+        self.last_scanned_offset = 0;
+        self.last_rendered_text.clear();
+        self.tool_error_counts.clear();
+        self.content_type_stats.clear();
+        self.session_stats.reset();
     }
 }
 
@@ -233,7 +520,7 @@ mod tests {
     #[test]
     fn test_detect_typescript_error() {
         let mut analyzer = StreamAnalyzer::new();
-        let result = analyzer.analyze("error TS2304: Cannot find name 'foo'");
+        let result = analyzer.analyze(b"error TS2304: Cannot find name 'foo'");
 
         assert!(result.content_types.iter().any(|ct| matches!(
             ct,
@@ -247,7 +534,7 @@ mod tests {
     #[test]
     fn test_detect_rust_error() {
         let mut analyzer = StreamAnalyzer::new();
-        let result = analyzer.analyze("error[E0425]: cannot find value `foo`");
+        let result = analyzer.analyze(b"error[E0425]: cannot find value `foo`");
 
         assert!(result.content_types.iter().any(|ct| matches!(
             ct,
@@ -261,7 +548,7 @@ mod tests {
     #[test]
     fn test_detect_python_error() {
         let mut analyzer = StreamAnalyzer::new();
-        let result = analyzer.analyze("NameError: name 'foo' is not defined");
+        let result = analyzer.analyze(b"NameError: name 'foo' is not defined");
 
         assert!(result.content_types.iter().any(|ct| matches!(
             ct,
@@ -275,7 +562,7 @@ mod tests {
     #[test]
     fn test_detect_go_error() {
         let mut analyzer = StreamAnalyzer::new();
-        let result = analyzer.analyze("undefined: foo");
+        let result = analyzer.analyze(b"undefined: foo");
 
         assert!(result.content_types.iter().any(|ct| matches!(
             ct,
@@ -289,7 +576,7 @@ mod tests {
     #[test]
     fn test_detect_file_read() {
         let mut analyzer = StreamAnalyzer::new();
-        let result = analyzer.analyze("Reading file: src/main.ts");
+        let result = analyzer.analyze(b"Reading file: src/main.ts");
 
         assert!(
             result
@@ -305,7 +592,7 @@ mod tests {
 
         // Générer un output volumineux
         let large_text = "x".repeat(6000);
-        let result = analyzer.analyze(&large_text);
+        let result = analyzer.analyze(large_text.as_bytes());
 
         assert!(
             result
@@ -318,7 +605,7 @@ mod tests {
     #[test]
     fn test_detect_prompt_ready() {
         let mut analyzer = StreamAnalyzer::new();
-        let result = analyzer.analyze("some output ❯");
+        let result = analyzer.analyze("some output ❯".as_bytes());
 
         assert!(
             result
@@ -329,18 +616,80 @@ mod tests {
     }
 
     #[test]
-    fn test_strip_ansi() {
-        let analyzer = StreamAnalyzer::new();
-        let text_with_ansi = "\x1b[31mError\x1b[0m: something failed";
-        let clean = analyzer.strip_ansi(text_with_ansi);
+    fn test_rendered_text_has_no_ansi() {
+        let mut analyzer = StreamAnalyzer::new();
+        let result = analyzer.analyze(b"\x1b[31mError\x1b[0m: something failed");
+
+        assert!(result.clean_text.contains("Error: something failed"));
+        assert!(!result.clean_text.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_detect_build_error_survives_split_escape_sequence() {
+        let mut analyzer = StreamAnalyzer::new();
+        // Séquence ANSI coupée entre deux reads du PTY
+        analyzer.analyze(b"\x1b[3");
+        let result = analyzer.analyze(b"1merror TS2304: Cannot find name 'foo'\x1b[0m");
+
+        assert!(result.content_types.iter().any(|ct| matches!(
+            ct,
+            ContentType::BuildError {
+                tool: BuildTool::TypeScript,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_error_not_double_counted_while_still_visible() {
+        let mut analyzer = StreamAnalyzer::new();
+        analyzer.analyze(b"error TS2304: Cannot find name 'foo'\n");
+        assert_eq!(analyzer.total_errors(), 1);
+
+        // Le message reste visible à l'écran; un chunk sans nouvelle
+        // erreur ne doit pas re-matcher/recompter celle déjà vue.
+        analyzer.analyze(b"some more output\n");
+        assert_eq!(analyzer.total_errors(), 1);
+    }
+
+    #[test]
+    fn test_file_read_not_re_reported_while_still_visible() {
+        let mut analyzer = StreamAnalyzer::new();
+        let first = analyzer.analyze(b"Reading file: src/main.ts\n");
+        assert!(
+            first
+                .content_types
+                .iter()
+                .any(|ct| matches!(ct, ContentType::FileRead { .. }))
+        );
+
+        let second = analyzer.analyze(b"still working...\n");
+        assert!(
+            !second
+                .content_types
+                .iter()
+                .any(|ct| matches!(ct, ContentType::FileRead { .. }))
+        );
+    }
+
+    #[test]
+    fn test_detection_window_is_configurable() {
+        let mut analyzer = StreamAnalyzer::new().with_detection_window(5);
+        // Fenêtre trop petite pour contenir le pattern entier: pas de détection
+        let result = analyzer.analyze(b"error TS2304: Cannot find name 'foo'");
 
-        assert_eq!(clean, "Error: something failed");
+        assert!(
+            !result
+                .content_types
+                .iter()
+                .any(|ct| matches!(ct, ContentType::BuildError { .. }))
+        );
     }
 
     #[test]
     fn test_normal_content() {
         let mut analyzer = StreamAnalyzer::new();
-        let result = analyzer.analyze("just some normal text");
+        let result = analyzer.analyze(b"just some normal text");
 
         assert!(
             result
@@ -354,7 +703,7 @@ mod tests {
     fn test_multiple_errors_count() {
         let mut analyzer = StreamAnalyzer::new();
         let text = "error TS2304: foo\nerror TS2304: bar\nerror TS2304: baz";
-        let result = analyzer.analyze(text);
+        let result = analyzer.analyze(text.as_bytes());
 
         if let Some(ContentType::BuildError { error_count, .. }) =
             result.content_types.iter().find(|ct| {
@@ -373,23 +722,167 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_register_detector_matches_custom_pattern() {
+        let mut analyzer = StreamAnalyzer::new();
+        analyzer
+            .register_detector("maven", r"(?i)\[ERROR\]", "maven_error")
+            .unwrap();
+
+        let result = analyzer.analyze(b"[ERROR] Failed to execute goal\n");
+
+        assert!(result.content_types.iter().any(
+            |ct| matches!(ct, ContentType::Custom { label, count } if label == "maven_error" && *count == 1)
+        ));
+    }
+
+    #[test]
+    fn test_register_detector_surfaces_invalid_regex() {
+        let mut analyzer = StreamAnalyzer::new();
+        assert!(analyzer
+            .register_detector("broken", "(unclosed", "broken_label")
+            .is_err());
+    }
+
+    #[test]
+    fn test_register_detector_does_not_double_count_while_still_visible() {
+        let mut analyzer = StreamAnalyzer::new();
+        analyzer
+            .register_detector("maven", r"(?i)\[ERROR\]", "maven_error")
+            .unwrap();
+
+        analyzer.analyze(b"[ERROR] Failed\n");
+        let second = analyzer.analyze(b"still building...\n");
+
+        assert!(!second
+            .content_types
+            .iter()
+            .any(|ct| matches!(ct, ContentType::Custom { .. })));
+    }
+
+    #[test]
+    fn test_analyze_compacts_repeated_error_lines() {
+        let mut analyzer = StreamAnalyzer::new();
+        let text =
+            "error TS2304: Cannot find name 'foo'\r\nerror TS2304: Cannot find name 'foo'\r\nerror TS2304: Cannot find name 'foo'\r\n";
+        let result = analyzer.analyze(text.as_bytes());
+
+        assert!(result.lines_saved > 0);
+        assert!(result.clean_text.contains("(×3)"));
+    }
+
     #[test]
     fn test_total_tokens() {
         let mut analyzer = StreamAnalyzer::new();
-        analyzer.analyze("hello world");
-        analyzer.analyze("more text");
+        analyzer.analyze(b"hello world");
+        analyzer.analyze(b"more text");
 
         assert!(analyzer.total_tokens() > 0);
     }
 
+    #[test]
+    fn test_token_estimate_is_scoped_to_the_chunk_not_the_whole_screen() {
+        let mut analyzer = StreamAnalyzer::new();
+        let chunk = "x".repeat(100);
+
+        let first = analyzer.analyze(chunk.as_bytes());
+        let after_first = analyzer.total_tokens();
+
+        for _ in 0..5 {
+            analyzer.analyze(chunk.as_bytes());
+        }
+        let after_six = analyzer.total_tokens();
+
+        assert!(first.token_estimate > 0);
+        // Avec l'ancien bug (estimation sur l'écran entier), le 6e appel à lui
+        // seul compterait l'équivalent de tout l'écran accumulé (~6x ce
+        // premier appel), et `total_tokens` grossirait en O(n²): la somme des
+        // 6 appels approcherait 21x `after_first` plutôt que ~6x.
+        assert!(
+            after_six <= after_first * 8,
+            "total_tokens grew quadratically with screen size: {after_first} -> {after_six}"
+        );
+    }
+
+    #[test]
+    fn test_rendered_delta_handles_scroll_without_duplicating_history() {
+        let mut analyzer = StreamAnalyzer::new();
+
+        let mut first_chunk = String::new();
+        for i in 0..210 {
+            first_chunk.push_str(&format!("line{i}\n"));
+        }
+        let first = analyzer.analyze(first_chunk.as_bytes());
+
+        let mut second_chunk = String::new();
+        for i in 210..220 {
+            second_chunk.push_str(&format!("line{i}\n"));
+        }
+        let second = analyzer.analyze(second_chunk.as_bytes());
+
+        // Le screen a scrollé entre les deux appels (>`SCREEN_ROWS` lignes
+        // cumulées): le delta du second appel ne doit contenir que les
+        // lignes qu'il a réellement ajoutées, pas les lignes déjà visibles
+        // au premier appel qui sont simplement restées à l'écran.
+        assert!(second.clean_text.contains("line219"));
+        assert!(!second.clean_text.contains("line20\n"));
+        // Un delta borné à 10 lignes coûte nettement moins de tokens qu'un
+        // écran entier de ~200 lignes.
+        assert!(second.token_estimate < first.token_estimate);
+    }
+
     #[test]
     fn test_reset() {
         let mut analyzer = StreamAnalyzer::new();
-        analyzer.analyze("error TS2304: foo");
+        analyzer.analyze(b"error TS2304: foo");
         analyzer.reset();
 
         assert_eq!(analyzer.total_tokens(), 0);
         assert_eq!(analyzer.total_errors(), 0);
         assert_eq!(analyzer.buffer_size(), 0);
     }
+
+    #[test]
+    fn test_content_type_stats_accumulates_occurrences_and_tokens() {
+        let mut analyzer = StreamAnalyzer::new();
+        analyzer.analyze(b"error TS2304: Cannot find name 'foo'\n");
+        analyzer.analyze(b"error TS2339: Property 'bar' does not exist\n");
+
+        let stats = analyzer.content_type_stats();
+        let build_errors = stats.get("build_error").expect("build_error bucket");
+        assert_eq!(build_errors.occurrences, 2);
+        assert!(build_errors.tokens > 0);
+    }
+
+    #[test]
+    fn test_content_type_stats_cleared_on_reset() {
+        let mut analyzer = StreamAnalyzer::new();
+        analyzer.analyze(b"error TS2304: foo");
+        analyzer.reset();
+
+        assert!(analyzer.content_type_stats().is_empty());
+    }
+
+    #[test]
+    fn test_session_stats_accumulates_chunks_and_build_errors() {
+        let mut analyzer = StreamAnalyzer::new();
+        analyzer.analyze(b"error TS2304: Cannot find name 'foo'\n");
+        analyzer.analyze(b"more text\n");
+
+        let summary = analyzer.session_stats().summary();
+        assert_eq!(summary.chunk_count, 2);
+        assert!(summary.total_bytes > 0);
+        assert_eq!(summary.tool_error_counts.get("tsc"), Some(&1));
+    }
+
+    #[test]
+    fn test_session_stats_cleared_on_reset() {
+        let mut analyzer = StreamAnalyzer::new();
+        analyzer.analyze(b"error TS2304: foo");
+        analyzer.reset();
+
+        let summary = analyzer.session_stats().summary();
+        assert_eq!(summary.chunk_count, 0);
+        assert!(summary.tool_error_counts.is_empty());
+    }
 }
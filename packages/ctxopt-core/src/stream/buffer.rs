@@ -13,6 +13,18 @@ pub struct RingBuffer {
 
     /// Capacité maximale
     capacity: usize,
+
+    /// Nombre total de caractères poussés depuis la création du buffer
+    /// (jamais réinitialisé par l'éviction, seulement par `clear`); sert de
+    /// repère absolu pour `line_offsets`
+    total_pushed: usize,
+
+    /// Offsets absolus (dans le repère `total_pushed`) du début de chaque
+    /// ligne rencontrée depuis le dernier `\n`, du plus ancien au plus
+    /// récent; purgé au fil de l'éviction pour ne garder que les lignes
+    /// encore présentes dans `data`. Permet à `last_n_lines` d'extraire des
+    /// lignes complètes sans retronçonner tout le buffer à chaque appel.
+    line_offsets: VecDeque<usize>,
 }
 
 impl RingBuffer {
@@ -21,6 +33,8 @@ impl RingBuffer {
         Self {
             data: VecDeque::with_capacity(capacity),
             capacity,
+            total_pushed: 0,
+            line_offsets: VecDeque::new(),
         }
     }
 
@@ -31,6 +45,16 @@ impl RingBuffer {
                 self.data.pop_front();
             }
             self.data.push_back(ch);
+            self.total_pushed += 1;
+            if ch == '\n' {
+                self.line_offsets.push_back(self.total_pushed);
+            }
+        }
+
+        // Purge les débuts de ligne qui sont tombés hors de la fenêtre `data`
+        let window_start = self.total_pushed.saturating_sub(self.data.len());
+        while self.line_offsets.front().is_some_and(|&offset| offset < window_start) {
+            self.line_offsets.pop_front();
         }
     }
 
@@ -43,6 +67,8 @@ impl RingBuffer {
     /// Vide le buffer
     pub fn clear(&mut self) {
         self.data.clear();
+        self.total_pushed = 0;
+        self.line_offsets.clear();
     }
 
     /// Retourne la taille actuelle (nombre de caractères)
@@ -62,6 +88,26 @@ impl RingBuffer {
         self.data.iter().skip(start).collect()
     }
 
+    /// Retourne les N dernières lignes complètes du buffer, alignées sur les
+    /// retours à la ligne (contrairement à `last_n`, qui peut tronquer au
+    /// milieu d'une ligne). S'appuie sur `line_offsets` plutôt que de
+    /// rescanner tout le buffer à chaque appel.
+    pub fn last_n_lines(&self, n: usize) -> String {
+        if n == 0 {
+            return String::new();
+        }
+
+        let window_start = self.total_pushed.saturating_sub(self.data.len());
+        let start_abs = if self.line_offsets.len() >= n {
+            self.line_offsets[self.line_offsets.len() - n]
+        } else {
+            window_start
+        };
+        let start_rel = start_abs.saturating_sub(window_start);
+
+        self.data.iter().skip(start_rel).collect()
+    }
+
     /// Retourne la capacité maximale (utilisé dans les tests)
     #[allow(dead_code)]
     pub fn capacity(&self) -> usize {
@@ -123,6 +169,31 @@ mod tests {
         assert_eq!(buf.content(), "a❯b");
     }
 
+    #[test]
+    fn test_last_n_lines_returns_complete_lines() {
+        let mut buf = RingBuffer::new(100);
+        buf.push("line one\nline two\nline three");
+        assert_eq!(buf.last_n_lines(2), "line two\nline three");
+        assert_eq!(buf.last_n_lines(1), "line three");
+        assert_eq!(buf.last_n_lines(10), "line one\nline two\nline three");
+    }
+
+    #[test]
+    fn test_last_n_lines_tracks_offsets_across_eviction() {
+        let mut buf = RingBuffer::new(10);
+        buf.push("a\nbb\nccc\ndddd\n");
+        // Capacity 10 only keeps the tail; earlier lines fell out entirely.
+        assert_eq!(buf.last_n_lines(1), "");
+        assert_eq!(buf.last_n_lines(2), "dddd\n");
+    }
+
+    #[test]
+    fn test_last_n_lines_zero_returns_empty() {
+        let mut buf = RingBuffer::new(100);
+        buf.push("hello\nworld");
+        assert_eq!(buf.last_n_lines(0), "");
+    }
+
     #[test]
     fn test_ring_buffer_incremental_push() {
         let mut buf = RingBuffer::new(10);
@@ -0,0 +1,79 @@
+//! Détecteurs enregistrables au runtime
+//!
+//! `StreamAnalyzer` ne reconnaît nativement que les outils de build câblés en
+//! dur dans `PATTERNS`. Les utilisateurs avec des toolchains maison (Maven,
+//! Gradle, pytest, cargo-nextest, ...) enregistrent ici une regex
+//! supplémentaire associée à un label; elle est évaluée après les détecteurs
+//! natifs, dans l'ordre d'enregistrement.
+
+use regex::Regex;
+use thiserror::Error;
+
+/// Erreur de compilation d'un pattern personnalisé
+#[derive(Error, Debug)]
+pub enum CustomPatternError {
+    #[error("invalid regex for custom detector {name:?}: {source}")]
+    InvalidRegex {
+        name: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// Détecteur personnalisé: un nom stable, une regex compilée une fois pour
+/// toutes à l'enregistrement, et le label à renvoyer dans `ContentType::Custom`.
+pub struct CustomDetector {
+    name: String,
+    pattern: Regex,
+    label: String,
+}
+
+impl CustomDetector {
+    /// Compile et construit un détecteur, ou renvoie l'erreur de regex
+    pub fn new(name: impl Into<String>, pattern: &str, label: impl Into<String>) -> Result<Self, CustomPatternError> {
+        let name = name.into();
+        let compiled = Regex::new(pattern).map_err(|source| CustomPatternError::InvalidRegex {
+            name: name.clone(),
+            source,
+        })?;
+        Ok(Self {
+            name,
+            pattern: compiled,
+            label: label.into(),
+        })
+    }
+
+    /// Nom stable du détecteur, tel que passé à `register_detector` (aucune
+    /// API de désenregistrement n'existe)
+    #[allow(dead_code)]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn pattern(&self) -> &Regex {
+        &self.pattern
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_compiles_valid_pattern() {
+        let detector = CustomDetector::new("maven", r"(?i)\[ERROR\]", "maven_error").unwrap();
+        assert_eq!(detector.name(), "maven");
+        assert_eq!(detector.label(), "maven_error");
+        assert!(detector.pattern().is_match("[ERROR] Build failed"));
+    }
+
+    #[test]
+    fn test_new_surfaces_invalid_regex() {
+        let err = CustomDetector::new("broken", "(unclosed", "broken_label");
+        assert!(matches!(err, Err(CustomPatternError::InvalidRegex { .. })));
+    }
+}
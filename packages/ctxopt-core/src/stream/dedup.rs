@@ -0,0 +1,206 @@
+//! Déduplication des lignes quasi-identiques (clustering par edit-distance)
+//!
+//! Les build tools et test runners répètent souvent des lignes quasi
+//! identiques (la même erreur TS par fichier, des frames de spinner, des
+//! redraws de progression): les compter est utile mais toutes les
+//! retransmettre coûte des tokens pour rien. On normalise chaque ligne
+//! (digits et chemins absolus remplacés par des placeholders) puis on
+//! fusionne les runs de lignes consécutives suffisamment proches (distance
+//! de Levenshtein bornée) sous une seule représentante suffixée `(×N)`.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Seuil de similarité par défaut pour fusionner deux lignes consécutives
+pub const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.85;
+
+/// Runs de chiffres, remplacés par un placeholder avant comparaison (les
+/// numéros de ligne/port/pid changent sans que la ligne soit "différente")
+static DIGIT_RUN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+/// Chemins absolus (Unix ou Windows), remplacés par un placeholder avant
+/// comparaison
+static ABS_PATH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:[A-Za-z]:\\[^\s]+|/[^\s]+)").unwrap());
+
+/// Résultat de la compaction d'un bloc de texte
+pub struct DedupResult {
+    /// Texte avec les runs de lignes quasi-identiques fusionnés
+    pub text: String,
+    /// Nombre de lignes économisées (fusionnées dans une représentante)
+    pub lines_saved: usize,
+}
+
+/// Normalise une ligne pour la comparaison: digits -> `#`, chemins absolus -> `<path>`
+fn normalize_line(line: &str) -> String {
+    let normalized = DIGIT_RUN.replace_all(line, "#");
+    ABS_PATH.replace_all(&normalized, "<path>").into_owned()
+}
+
+/// Distance de Levenshtein bornée par une coupe en bande: s'arrête dès que
+/// le minimum de la ligne de DP courante dépasse `max_distance` (les deux
+/// lignes sont alors trop différentes, peu importe la distance exacte),
+/// ce qui garde le calcul quasi-linéaire pour des lignes longues mais peu
+/// similaires.
+fn levenshtein_bounded(a: &[char], b: &[char], max_distance: usize) -> Option<usize> {
+    let (n, m) = (a.len(), b.len());
+    if n.abs_diff(m) > max_distance {
+        return None;
+    }
+
+    let mut prev: Vec<usize> = (0..=m).collect();
+    let mut curr = vec![0usize; m + 1];
+
+    for i in 1..=n {
+        curr[0] = i;
+        let mut row_min = curr[0];
+
+        for j in 1..=m {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+            row_min = row_min.min(curr[j]);
+        }
+
+        if row_min > max_distance {
+            return None;
+        }
+
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    Some(prev[m])
+}
+
+/// `true` si la similarité `1 - distance/max(len_a, len_b)` atteint `threshold`
+fn is_similar(a: &[char], b: &[char], threshold: f64) -> bool {
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return true;
+    }
+
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    let max_distance = ((1.0 - threshold) * max_len as f64).floor() as usize;
+
+    levenshtein_bounded(a, b, max_distance).is_some()
+}
+
+fn render_run(representative: &str, count: usize) -> String {
+    if count > 1 {
+        format!("{representative} (×{count})")
+    } else {
+        representative.to_string()
+    }
+}
+
+/// Fusionne les runs de lignes consécutives quasi-identiques de `text`
+///
+/// Deux lignes sont regroupées dans le même run si leurs versions
+/// normalisées atteignent `threshold` de similarité l'une par rapport à
+/// l'autre.
+pub fn compact_lines(text: &str, threshold: f64) -> DedupResult {
+    let lines: Vec<&str> = text.lines().collect();
+    let Some((&first, rest)) = lines.split_first() else {
+        return DedupResult {
+            text: String::new(),
+            lines_saved: 0,
+        };
+    };
+
+    let mut out_lines = Vec::new();
+    let mut lines_saved = 0usize;
+
+    let mut run_repr = first;
+    let mut run_repr_norm: Vec<char> = normalize_line(run_repr).chars().collect();
+    let mut run_count = 1usize;
+
+    for &line in rest {
+        let norm: Vec<char> = normalize_line(line).chars().collect();
+
+        if is_similar(&run_repr_norm, &norm, threshold) {
+            run_count += 1;
+            lines_saved += 1;
+        } else {
+            out_lines.push(render_run(run_repr, run_count));
+            run_repr = line;
+            run_repr_norm = norm;
+            run_count = 1;
+        }
+    }
+    out_lines.push(render_run(run_repr, run_count));
+
+    DedupResult {
+        text: out_lines.join("\n"),
+        lines_saved,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein_bounded_identical() {
+        let a: Vec<char> = "hello".chars().collect();
+        assert_eq!(levenshtein_bounded(&a, &a, 5), Some(0));
+    }
+
+    #[test]
+    fn test_levenshtein_bounded_within_budget() {
+        let a: Vec<char> = "kitten".chars().collect();
+        let b: Vec<char> = "sitting".chars().collect();
+        assert_eq!(levenshtein_bounded(&a, &b, 5), Some(3));
+    }
+
+    #[test]
+    fn test_levenshtein_bounded_bails_early() {
+        let a: Vec<char> = "completely different text".chars().collect();
+        let b: Vec<char> = "not at all the same line!!".chars().collect();
+        assert_eq!(levenshtein_bounded(&a, &b, 2), None);
+    }
+
+    #[test]
+    fn test_compact_lines_collapses_repeated_error() {
+        let text = "error TS2304: Cannot find name 'foo'\nerror TS2304: Cannot find name 'foo'\nerror TS2304: Cannot find name 'foo'";
+        let result = compact_lines(text, DEFAULT_SIMILARITY_THRESHOLD);
+
+        assert_eq!(result.lines_saved, 2);
+        assert!(result.text.contains("(×3)"));
+        assert_eq!(result.text.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_compact_lines_normalizes_digits_and_paths() {
+        let text = "Reading file: /Users/alice/project/src/main.ts:10\nReading file: /Users/bob/project/src/main.ts:99";
+        let result = compact_lines(text, DEFAULT_SIMILARITY_THRESHOLD);
+
+        assert_eq!(result.lines_saved, 1);
+        assert!(result.text.contains("(×2)"));
+    }
+
+    #[test]
+    fn test_compact_lines_keeps_dissimilar_lines_separate() {
+        let text = "error TS2304: Cannot find name 'foo'\nSuccessfully compiled 42 files";
+        let result = compact_lines(text, DEFAULT_SIMILARITY_THRESHOLD);
+
+        assert_eq!(result.lines_saved, 0);
+        assert_eq!(result.text.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_compact_lines_empty_input() {
+        let result = compact_lines("", DEFAULT_SIMILARITY_THRESHOLD);
+        assert_eq!(result.lines_saved, 0);
+        assert_eq!(result.text, "");
+    }
+
+    #[test]
+    fn test_compact_lines_only_merges_consecutive_runs() {
+        // Le run identique réapparaît mais séparé par une ligne différente:
+        // on ne doit PAS fusionner à travers la ligne différente.
+        let text = "error TS2304: foo\nerror TS2304: foo\nBuilding...\nerror TS2304: foo";
+        let result = compact_lines(text, DEFAULT_SIMILARITY_THRESHOLD);
+
+        assert_eq!(result.lines_saved, 1);
+        assert_eq!(result.text.lines().count(), 3);
+    }
+}
@@ -0,0 +1,148 @@
+//! Évènements d'analyse sérialisables, en JSON délimité par des sauts de ligne
+//!
+//! `AnalysisResult` ne vit qu'en mémoire: rien ne permet à un agent/proxy
+//! qui encapsule un shell de streamer ce qui a été détecté vers un sink de
+//! métriques ou une UI séparée. `AnalysisEvent` est la version à plat,
+//! `Serialize`/`Deserialize` (comme les structs de wire format du crate),
+//! d'un `AnalysisResult`: elle peut être écrite en JSON une ligne par appel,
+//! et rejouée telle quelle dans les tests pour reproduire déterministement
+//! un flux capturé sans repasser par le PTY.
+
+use super::analyzer::AnalysisResult;
+use super::patterns::ContentType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Version à plat d'un `ContentType`, pour la sérialisation
+///
+/// `ContentType` est un simple enum Rust; cette version porte les mêmes
+/// données mais avec un tag `kind` explicite pour rester lisible côté
+/// consommateur JSON non-Rust.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContentTypeEvent {
+    BuildError { tool: String, error_count: usize },
+    FileRead { file_path: String },
+    LargeOutput { size: usize },
+    PromptReady,
+    Custom { label: String, count: usize },
+    Normal,
+}
+
+impl From<&ContentType> for ContentTypeEvent {
+    fn from(content_type: &ContentType) -> Self {
+        match content_type {
+            ContentType::BuildError { error_count, tool } => Self::BuildError {
+                tool: tool.as_str().to_string(),
+                error_count: *error_count,
+            },
+            ContentType::FileRead { file_path } => Self::FileRead {
+                file_path: file_path.clone(),
+            },
+            ContentType::LargeOutput { size } => Self::LargeOutput { size: *size },
+            ContentType::PromptReady => Self::PromptReady,
+            ContentType::Custom { label, count } => Self::Custom {
+                label: label.clone(),
+                count: *count,
+            },
+            ContentType::Normal => Self::Normal,
+        }
+    }
+}
+
+/// Enregistrement sérialisable d'un appel à `StreamAnalyzer::analyze`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisEvent {
+    /// Types de contenu détectés pour ce chunk
+    pub content_types: Vec<ContentTypeEvent>,
+    /// Estimation de tokens pour ce chunk
+    pub token_estimate: usize,
+    /// Total de tokens cumulé depuis le dernier reset
+    pub total_tokens: usize,
+    /// Total d'erreurs cumulé depuis le dernier reset
+    pub total_errors: usize,
+    /// Chemin du fichier lu, si un `FileRead` a été détecté dans ce chunk
+    pub detected_file_path: Option<String>,
+    /// Compteur d'erreurs cumulé par outil (clé: `BuildTool::as_str()`)
+    pub tool_error_counts: HashMap<String, usize>,
+}
+
+impl AnalysisEvent {
+    /// Construit l'évènement à partir d'un résultat d'analyse et des
+    /// compteurs cumulés de l'analyseur
+    pub(super) fn from_result(
+        result: &AnalysisResult,
+        total_tokens: usize,
+        total_errors: usize,
+        tool_error_counts: HashMap<String, usize>,
+    ) -> Self {
+        let content_types: Vec<ContentTypeEvent> =
+            result.content_types.iter().map(ContentTypeEvent::from).collect();
+
+        let detected_file_path = result.content_types.iter().find_map(|ct| match ct {
+            ContentType::FileRead { file_path } => Some(file_path.clone()),
+            _ => None,
+        });
+
+        Self {
+            content_types,
+            token_estimate: result.token_estimate,
+            total_tokens,
+            total_errors,
+            detected_file_path,
+            tool_error_counts,
+        }
+    }
+
+    /// Sérialise l'évènement en une ligne JSON (format newline-delimited)
+    pub fn to_json_line(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::analyzer::StreamAnalyzer;
+
+    #[test]
+    fn test_analyze_event_reports_build_error() {
+        let mut analyzer = StreamAnalyzer::new();
+        let (_, event) = analyzer.analyze_with_event(b"error TS2304: Cannot find name 'foo'");
+
+        assert_eq!(event.total_errors, 1);
+        assert_eq!(event.tool_error_counts.get("tsc"), Some(&1));
+        assert!(event
+            .content_types
+            .iter()
+            .any(|ct| matches!(ct, ContentTypeEvent::BuildError { tool, .. } if tool == "tsc")));
+    }
+
+    #[test]
+    fn test_analyze_event_reports_detected_file_path() {
+        let mut analyzer = StreamAnalyzer::new();
+        let (_, event) = analyzer.analyze_with_event(b"Reading file: src/main.ts");
+
+        assert_eq!(event.detected_file_path.as_deref(), Some("src/main.ts"));
+    }
+
+    #[test]
+    fn test_to_json_line_round_trips() {
+        let mut analyzer = StreamAnalyzer::new();
+        let (_, event) = analyzer.analyze_with_event(b"error TS2304: Cannot find name 'foo'");
+
+        let line = event.to_json_line().unwrap();
+        let parsed: AnalysisEvent = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed, event);
+    }
+
+    #[test]
+    fn test_tool_error_counts_accumulate_across_calls() {
+        let mut analyzer = StreamAnalyzer::new();
+        analyzer.analyze_with_event(b"error TS2304: foo\n");
+        let (_, event) = analyzer.analyze_with_event(b"error[E0425]: cannot find value `bar`\n");
+
+        assert_eq!(event.tool_error_counts.get("tsc"), Some(&1));
+        assert_eq!(event.tool_error_counts.get("cargo"), Some(&1));
+    }
+}
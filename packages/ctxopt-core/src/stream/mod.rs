@@ -8,7 +8,15 @@
 
 pub(crate) mod analyzer;
 pub(crate) mod buffer;
+pub(crate) mod custom;
+pub(crate) mod dedup;
+pub(crate) mod events;
 pub(crate) mod patterns;
+pub(crate) mod screen;
+pub(crate) mod shared;
+pub(crate) mod stats;
 
-pub(crate) use analyzer::StreamAnalyzer;
-pub(crate) use patterns::PATTERNS;
+pub(crate) use analyzer::{ContentTypeCounters, StreamAnalyzer};
+pub(crate) use patterns::{ContentType, PATTERNS};
+pub(crate) use shared::SharedAnalyzer;
+pub(crate) use stats::{DistributionSummary, SessionStats, SessionSummary};
@@ -32,10 +32,39 @@ pub enum ContentType {
     /// Claude est prêt pour une entrée
     PromptReady,
 
+    /// Match d'un détecteur enregistré au runtime (voir `stream::custom`)
+    Custom {
+        /// Label fourni à l'enregistrement du détecteur
+        label: String,
+        /// Nombre de matches (nouveaux, pas déjà comptés)
+        count: usize,
+    },
+
     /// Contenu normal (pas de pattern détecté)
     Normal,
 }
 
+impl ContentType {
+    /// Discriminant stable du type de contenu, utilisé comme clé pour
+    /// l'agrégation par type (voir `StreamAnalyzer::content_type_stats` et
+    /// `ContextInjector::kind_stats`, combinés par
+    /// `CtxOptSession::stats_breakdown`, inspirés du `CodeStats` de rustc).
+    ///
+    /// Renvoie une `String` plutôt qu'un `&'static str` car `Custom` a un
+    /// label dynamique: chaque détecteur enregistré au runtime compte comme
+    /// son propre "bucket" (ex: `custom:maven`, `custom:pytest`).
+    pub fn kind(&self) -> String {
+        match self {
+            ContentType::BuildError { .. } => "build_error".to_string(),
+            ContentType::FileRead { .. } => "file_read".to_string(),
+            ContentType::LargeOutput { .. } => "large_output".to_string(),
+            ContentType::PromptReady => "prompt_ready".to_string(),
+            ContentType::Custom { label, .. } => format!("custom:{label}"),
+            ContentType::Normal => "normal".to_string(),
+        }
+    }
+}
+
 /// Outils de build reconnus
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub enum BuildTool {
@@ -94,9 +123,6 @@ pub struct Patterns {
 
     /// Prompt ready (❯, >, $)
     pub prompt_ready: Regex,
-
-    /// ANSI escape codes (pour stripping)
-    pub ansi_escape: Regex,
 }
 
 impl Patterns {
@@ -135,11 +161,9 @@ impl Patterns {
             )
             .unwrap(),
 
-            // Prompt ready (fin de ligne avec prompt shell)
+            // Prompt ready: appliqué sur le texte de la ligne sous le curseur
+            // (rendu par `TerminalScreen`), donc pas besoin d'ancrer en fin de chaîne.
             prompt_ready: Regex::new(r"(❯|>\s*$|\$\s*$|claude\s*>\s*$)").unwrap(),
-
-            // ANSI escape sequences
-            ansi_escape: Regex::new(r"\x1b\[[0-9;]*[a-zA-Z]|\x1b\].*?\x07").unwrap(),
         }
     }
 }
@@ -154,6 +178,27 @@ impl Default for Patterns {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_content_type_kind_discriminants() {
+        assert_eq!(
+            ContentType::BuildError { error_count: 1, tool: BuildTool::Rust }.kind(),
+            "build_error"
+        );
+        assert_eq!(
+            ContentType::FileRead { file_path: "src/main.rs".to_string() }.kind(),
+            "file_read"
+        );
+        assert_eq!(ContentType::LargeOutput { size: 1 }.kind(), "large_output");
+        assert_eq!(ContentType::PromptReady.kind(), "prompt_ready");
+        assert_eq!(ContentType::Normal.kind(), "normal");
+    }
+
+    #[test]
+    fn test_content_type_kind_custom_includes_label() {
+        let custom = ContentType::Custom { label: "maven".to_string(), count: 1 };
+        assert_eq!(custom.kind(), "custom:maven");
+    }
+
     #[test]
     fn test_typescript_pattern() {
         assert!(PATTERNS
@@ -213,13 +258,6 @@ mod tests {
         assert!(PATTERNS.prompt_ready.is_match("user@host:~$"));
     }
 
-    #[test]
-    fn test_ansi_escape_pattern() {
-        let text = "\x1b[31mError\x1b[0m: something failed";
-        let clean = PATTERNS.ansi_escape.replace_all(text, "");
-        assert_eq!(clean, "Error: something failed");
-    }
-
     #[test]
     fn test_build_tool_as_str() {
         assert_eq!(BuildTool::TypeScript.as_str(), "tsc");
@@ -0,0 +1,122 @@
+//! Modèle d'écran terminal (vt100) pour l'analyse du flux
+//!
+//! Remplace le stripping ANSI par regex par une vraie émulation de
+//! terminal: on alimente un `vt100::Parser` avec chaque chunk de bytes
+//! lu du PTY, et on lit le texte rendu (stable, sans séquences
+//! d'échappement) depuis la grille résultante plutôt que depuis les
+//! bytes bruts. Ça gère correctement les redraws (cursor moves, clear
+//! de ligne) et les séquences d'échappement coupées entre deux chunks.
+
+/// Dimensions de la grille virtuelle
+///
+/// Volontairement plus grande qu'un terminal réel pour que le texte
+/// d'un seul chunk tienne dans l'écran visible sans scroller, ce qui
+/// garde l'analyse par-chunk stable même pour de gros outputs.
+const SCREEN_ROWS: u16 = 200;
+const SCREEN_COLS: u16 = 300;
+
+/// Lignes de scrollback conservées au-delà de l'écran visible
+const SCROLLBACK_LEN: usize = 2000;
+
+/// Enveloppe autour de `vt100::Parser` exposant le texte rendu
+pub struct TerminalScreen {
+    parser: vt100::Parser,
+}
+
+impl TerminalScreen {
+    /// Crée un nouvel écran virtuel vide
+    pub fn new() -> Self {
+        Self {
+            parser: vt100::Parser::new(SCREEN_ROWS, SCREEN_COLS, SCROLLBACK_LEN),
+        }
+    }
+
+    /// Traite un chunk de bytes bruts (peut contenir des séquences
+    /// d'échappement partielles, `vt100` les recolle entre deux appels)
+    pub fn process(&mut self, bytes: &[u8]) {
+        self.parser.process(bytes);
+    }
+
+    /// Texte rendu de l'écran visible, lignes jointes par `\n`, sans ANSI
+    pub fn visible_text(&self) -> String {
+        self.parser.screen().contents()
+    }
+
+    /// Texte de la ligne sous le curseur, sans ANSI
+    ///
+    /// Plus fiable qu'une regex de fin de ligne pour détecter un prompt
+    /// qui attend une entrée: le curseur est toujours positionné là où
+    /// l'utilisateur taperait la prochaine touche.
+    pub fn cursor_row_text(&self) -> String {
+        let screen = self.parser.screen();
+        let (row, _col) = screen.cursor_position();
+        screen
+            .contents()
+            .lines()
+            .nth(row as usize)
+            .unwrap_or("")
+            .to_string()
+    }
+
+    /// Réinitialise l'écran (nouvelle session logique)
+    pub fn reset(&mut self) {
+        self.parser = vt100::Parser::new(SCREEN_ROWS, SCREEN_COLS, SCROLLBACK_LEN);
+    }
+}
+
+impl Default for TerminalScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_ansi_from_visible_text() {
+        let mut screen = TerminalScreen::new();
+        screen.process(b"\x1b[31mError\x1b[0m: something failed");
+
+        assert!(screen.visible_text().contains("Error: something failed"));
+        assert!(!screen.visible_text().contains('\x1b'));
+    }
+
+    #[test]
+    fn test_cursor_tracks_redraw() {
+        let mut screen = TerminalScreen::new();
+        // Écrit "old", revient au début de ligne, réécrit "new"
+        screen.process(b"old\rnew");
+
+        assert!(screen.visible_text().contains("new"));
+    }
+
+    #[test]
+    fn test_handles_split_escape_sequence() {
+        let mut screen = TerminalScreen::new();
+        // Séquence coupée en deux chunks, comme un read() PTY pourrait le livrer
+        screen.process(b"\x1b[3");
+        screen.process(b"1mError\x1b[0m");
+
+        assert!(screen.visible_text().contains("Error"));
+    }
+
+    #[test]
+    fn test_cursor_row_text_matches_prompt_line() {
+        let mut screen = TerminalScreen::new();
+        screen.process(b"some output\n\x1b[32m\xe2\x9d\xaf \x1b[0m");
+
+        assert!(screen.cursor_row_text().contains('\u{276F}'));
+    }
+
+    #[test]
+    fn test_reset_clears_screen() {
+        let mut screen = TerminalScreen::new();
+        screen.process(b"hello");
+        assert!(screen.visible_text().contains("hello"));
+
+        screen.reset();
+        assert!(!screen.visible_text().contains("hello"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Analyseur partagé, utilisable concurremment depuis plusieurs tâches tokio
+//!
+//! `StreamAnalyzer::analyze` exige `&mut self` (seuls les accesseurs en
+//! lecture comme `total_tokens` se contentent de `&self`), donc il ne peut pas être partagé tel
+//! quel entre la tâche qui nourrit les chunks et les tâches qui interrogent
+//! les statistiques. `lock_benchmarks::bench_lock_contention` montre que
+//! `RwLock` bat `Mutex` dès que la charge est read-heavy (plusieurs lecteurs
+//! concurrents pour un seul écrivain), ce qui correspond exactement à ce
+//! déploiement: `feed` est appelé une fois par chunk PTY, `total_tokens`/
+//! `total_errors`/`snapshot` peuvent être interrogés en continu par un
+//! dashboard. D'où `RwLock` plutôt que `Mutex` ici.
+use super::analyzer::{AnalysisResult, ContentTypeCounters, StreamAnalyzer};
+use super::stats::SessionSummary;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Instantané immuable des compteurs de l'analyseur à un instant donné
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnalyzerSnapshot {
+    pub total_tokens: usize,
+    pub total_errors: usize,
+}
+
+/// Wrapper `RwLock` autour de `StreamAnalyzer`, pour un partage multi-tâches
+pub struct SharedAnalyzer {
+    inner: RwLock<StreamAnalyzer>,
+}
+
+impl SharedAnalyzer {
+    /// Crée un nouvel analyseur partagé
+    pub fn new() -> Self {
+        Self {
+            inner: RwLock::new(StreamAnalyzer::new()),
+        }
+    }
+
+    /// Analyse un chunk, en tenant le verrou en écriture le temps strict de
+    /// l'appel à `analyze` (la seule opération mutante du chemin chaud)
+    pub async fn feed(&self, chunk: &[u8]) -> AnalysisResult {
+        self.inner.write().await.analyze(chunk)
+    }
+
+    /// Total de tokens estimés, lisible concurremment avec d'autres lecteurs
+    pub async fn total_tokens(&self) -> usize {
+        self.inner.read().await.total_tokens()
+    }
+
+    /// Total d'erreurs détectées, lisible concurremment avec d'autres lecteurs
+    pub async fn total_errors(&self) -> usize {
+        self.inner.read().await.total_errors()
+    }
+
+    /// Instantané cohérent des deux compteurs sous un seul verrou en lecture
+    pub async fn snapshot(&self) -> AnalyzerSnapshot {
+        let analyzer = self.inner.read().await;
+        AnalyzerSnapshot {
+            total_tokens: analyzer.total_tokens(),
+            total_errors: analyzer.total_errors(),
+        }
+    }
+
+    /// Reset les compteurs (nouvelle session)
+    pub async fn reset(&self) {
+        self.inner.write().await.reset();
+    }
+
+    /// Dernières `n` lignes complètes du buffer, pour le rendu annoté des
+    /// suggestions (voir `Suggestion::format_annotated`)
+    pub async fn recent_lines(&self, n: usize) -> String {
+        self.inner.read().await.recent_lines(n)
+    }
+
+    /// Copie des compteurs agrégés par type de contenu, lisible concurremment
+    /// avec `feed` (voir `StreamAnalyzer::content_type_stats`)
+    pub async fn content_type_stats(&self) -> HashMap<String, ContentTypeCounters> {
+        self.inner.read().await.content_type_stats().clone()
+    }
+
+    /// Rapport final des statistiques robustes accumulées depuis le dernier
+    /// `reset` (voir `StreamAnalyzer::session_stats`)
+    pub async fn session_summary(&self) -> SessionSummary {
+        self.inner.read().await.session_stats().summary()
+    }
+
+    /// Enregistre une suggestion émise par `ContextInjector` pour ce chunk
+    /// (clé: `SuggestionType::as_str()`). Séparé de `feed` car l'injection
+    /// se décide après l'analyse, sur un verrou distinct (`ContextInjector`)
+    pub async fn record_suggestion(&self, suggestion_type: &str) {
+        self.inner
+            .write()
+            .await
+            .session_stats_mut()
+            .record_suggestion(suggestion_type);
+    }
+}
+
+impl Default for SharedAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_feed_updates_totals() {
+        let shared = SharedAnalyzer::new();
+        shared.feed(b"hello world").await;
+
+        assert!(shared.total_tokens().await > 0);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_is_consistent() {
+        let shared = SharedAnalyzer::new();
+        shared.feed(b"error TS2304: Cannot find name 'foo'").await;
+
+        let snapshot = shared.snapshot().await;
+        assert_eq!(snapshot.total_errors, 1);
+        assert!(snapshot.total_tokens > 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_reads_while_feeding() {
+        let shared = Arc::new(SharedAnalyzer::new());
+        shared.feed(b"warming up").await;
+
+        let readers: Vec<_> = (0..8)
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                tokio::spawn(async move { shared.total_tokens().await })
+            })
+            .collect();
+
+        for reader in readers {
+            reader.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recent_lines_delegates_to_inner_analyzer() {
+        let shared = SharedAnalyzer::new();
+        shared.feed(b"line one\nline two").await;
+
+        assert_eq!(shared.recent_lines(1).await, "line two");
+    }
+
+    #[tokio::test]
+    async fn test_content_type_stats_tracks_occurrences() {
+        let shared = SharedAnalyzer::new();
+        shared.feed(b"error TS2304: Cannot find name 'foo'").await;
+
+        let stats = shared.content_type_stats().await;
+        assert_eq!(stats["build_error"].occurrences, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reset_clears_totals() {
+        let shared = SharedAnalyzer::new();
+        shared.feed(b"error TS2304: foo").await;
+        shared.reset().await;
+
+        let snapshot = shared.snapshot().await;
+        assert_eq!(snapshot.total_tokens, 0);
+        assert_eq!(snapshot.total_errors, 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_summary_tracks_chunks_and_suggestions() {
+        let shared = SharedAnalyzer::new();
+        shared.feed(b"error TS2304: foo").await;
+        shared.record_suggestion("build_errors").await;
+
+        let summary = shared.session_summary().await;
+        assert_eq!(summary.chunk_count, 1);
+        assert_eq!(summary.suggestion_counts.get("build_errors"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_session_summary_cleared_on_reset() {
+        let shared = SharedAnalyzer::new();
+        shared.feed(b"error TS2304: foo").await;
+        shared.reset().await;
+
+        let summary = shared.session_summary().await;
+        assert_eq!(summary.chunk_count, 0);
+    }
+}
@@ -0,0 +1,296 @@
+//! Statistiques robustes accumulées sur toute la durée d'exécution
+//!
+//! `StreamAnalyzer` expose déjà des compteurs au fil de l'eau (`total_tokens`,
+//! `total_errors`, `content_type_stats`), mais aucun d'eux ne dit si un chunk
+//! de 50KB est un cas exceptionnel ou la norme de cette session. `Distribution`
+//! reprend le module `test::stats` de libtest: les échantillons sont triés
+//! une fois, les percentiles utilisent la méthode "nearest-rank" (pas
+//! d'interpolation), et `winsorized_mean` permet d'écarter l'effet de
+//! quelques gros dumps de log isolés sur la taille de chunk "typique".
+//! `SessionStats` accumule les distributions de taille/tokens sur toute la
+//! durée de vie de l'analyseur et les combine en un rapport final.
+
+use std::collections::HashMap;
+
+/// Distribution de valeurs numériques avec des statistiques robustes aux outliers
+#[derive(Debug, Clone)]
+pub struct Distribution {
+    /// Échantillons triés en ordre croissant
+    sorted: Vec<f64>,
+}
+
+impl Distribution {
+    /// Construit une distribution à partir d'échantillons non triés
+    pub fn new(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        Self { sorted: samples }
+    }
+
+    /// Percentile par la méthode "nearest-rank": `index = ceil(p/100 * n) - 1`,
+    /// borné à `[0, n-1]`. Renvoie 0 pour une distribution vide.
+    pub fn percentile(&self, p: f64) -> f64 {
+        let n = self.sorted.len();
+        if n == 0 {
+            return 0.0;
+        }
+
+        let idx = ((p / 100.0 * n as f64).ceil() as usize)
+            .saturating_sub(1)
+            .min(n - 1);
+        self.sorted[idx]
+    }
+
+    /// Médiane (percentile 50)
+    pub fn median(&self) -> f64 {
+        self.percentile(50.0)
+    }
+
+    /// Premier quartile (percentile 25)
+    pub fn q1(&self) -> f64 {
+        self.percentile(25.0)
+    }
+
+    /// Troisième quartile (percentile 75)
+    pub fn q3(&self) -> f64 {
+        self.percentile(75.0)
+    }
+
+    /// Moyenne arithmétique, 0 pour une distribution vide
+    pub fn mean(&self) -> f64 {
+        if self.sorted.is_empty() {
+            return 0.0;
+        }
+        self.sorted.iter().sum::<f64>() / self.sorted.len() as f64
+    }
+
+    /// Écart-type d'échantillon (divise par `n - 1`), 0 si `n < 2`
+    pub fn std_dev(&self) -> f64 {
+        let n = self.sorted.len();
+        if n < 2 {
+            return 0.0;
+        }
+
+        let mean = self.mean();
+        let variance =
+            self.sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n as f64 - 1.0);
+        variance.sqrt()
+    }
+
+    /// Moyenne winsorisée: les `pct`% de valeurs les plus basses et les plus
+    /// hautes sont ramenées à la valeur du seuil correspondant avant de
+    /// moyenner, pour qu'un gros dump de log isolé ne fausse pas la taille
+    /// de chunk "typique". `pct` au-delà de 50 est replié sur la moitié de
+    /// l'échantillon (winsorisation totale, équivalente à la médiane).
+    pub fn winsorized_mean(&self, pct: f64) -> f64 {
+        let n = self.sorted.len();
+        if n == 0 {
+            return 0.0;
+        }
+
+        let cut = ((pct / 100.0 * n as f64).floor() as usize).min((n.saturating_sub(1)) / 2);
+        if cut == 0 {
+            return self.mean();
+        }
+
+        let low = self.sorted[cut];
+        let high = self.sorted[n - 1 - cut];
+        self.sorted.iter().map(|&v| v.clamp(low, high)).sum::<f64>() / n as f64
+    }
+}
+
+/// Pourcentage de winsorisation utilisé par `SessionStats::summary` pour les
+/// distributions de taille de chunk et d'estimation de tokens
+const WINSORIZE_PCT: f64 = 5.0;
+
+/// Résumé figé d'une [`Distribution`], pour le rapport final
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DistributionSummary {
+    pub mean: f64,
+    pub median: f64,
+    pub q1: f64,
+    pub q3: f64,
+    pub std_dev: f64,
+    pub winsorized_mean: f64,
+}
+
+impl From<&Distribution> for DistributionSummary {
+    fn from(dist: &Distribution) -> Self {
+        Self {
+            mean: dist.mean(),
+            median: dist.median(),
+            q1: dist.q1(),
+            q3: dist.q3(),
+            std_dev: dist.std_dev(),
+            winsorized_mean: dist.winsorized_mean(WINSORIZE_PCT),
+        }
+    }
+}
+
+/// Statistiques accumulées sur toute la durée d'exécution de la commande
+/// wrappée, au-delà des nudges par chunk de `ContextInjector`
+///
+/// Alimentée chunk par chunk par `StreamAnalyzer::analyze` (voir
+/// `StreamAnalyzer::session_stats`); `record_suggestion` est laissée à la
+/// charge de l'appelant (ex: `CtxOptSession`, qui voit à la fois l'analyse
+/// et l'injection) pour ne pas faire dépendre ce module du type
+/// `SuggestionType` de `injector`.
+#[derive(Debug, Clone, Default)]
+pub struct SessionStats {
+    total_bytes: usize,
+    chunk_sizes: Vec<f64>,
+    token_estimates: Vec<f64>,
+    tool_error_counts: HashMap<String, usize>,
+    suggestion_counts: HashMap<String, usize>,
+}
+
+impl SessionStats {
+    /// Crée des statistiques vides
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre un chunk analysé
+    pub fn record_chunk(&mut self, size_bytes: usize, token_estimate: usize) {
+        self.total_bytes += size_bytes;
+        self.chunk_sizes.push(size_bytes as f64);
+        self.token_estimates.push(token_estimate as f64);
+    }
+
+    /// Accumule des erreurs de build pour un outil (clé: `BuildTool::as_str()`)
+    pub fn record_build_errors(&mut self, tool: &str, count: usize) {
+        *self.tool_error_counts.entry(tool.to_string()).or_insert(0) += count;
+    }
+
+    /// Compte une suggestion émise (clé: `SuggestionType::as_str()`)
+    pub fn record_suggestion(&mut self, suggestion_type: &str) {
+        *self
+            .suggestion_counts
+            .entry(suggestion_type.to_string())
+            .or_insert(0) += 1;
+    }
+
+    /// Calcule le rapport final
+    pub fn summary(&self) -> SessionSummary {
+        SessionSummary {
+            total_bytes: self.total_bytes,
+            chunk_count: self.chunk_sizes.len(),
+            chunk_size: DistributionSummary::from(&Distribution::new(self.chunk_sizes.clone())),
+            token_estimate: DistributionSummary::from(&Distribution::new(
+                self.token_estimates.clone(),
+            )),
+            tool_error_counts: self.tool_error_counts.clone(),
+            suggestion_counts: self.suggestion_counts.clone(),
+        }
+    }
+
+    /// Reset les compteurs
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Rapport final de [`SessionStats::summary`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    /// Octets totaux vus depuis le dernier reset
+    pub total_bytes: usize,
+    /// Nombre de chunks analysés
+    pub chunk_count: usize,
+    /// Distribution des tailles de chunk (en octets)
+    pub chunk_size: DistributionSummary,
+    /// Distribution des estimations de tokens par chunk
+    pub token_estimate: DistributionSummary,
+    /// Erreurs de build cumulées par outil
+    pub tool_error_counts: HashMap<String, usize>,
+    /// Suggestions émises par type
+    pub suggestion_counts: HashMap<String, usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_matches_nearest_rank_formula() {
+        let dist = Distribution::new(vec![10.0, 20.0, 30.0, 40.0]);
+        // n = 4, p = 25 -> ceil(0.25 * 4) - 1 = 0
+        assert_eq!(dist.percentile(25.0), 10.0);
+        // p = 75 -> ceil(0.75 * 4) - 1 = 2
+        assert_eq!(dist.percentile(75.0), 30.0);
+    }
+
+    #[test]
+    fn test_median_odd_and_even_sample_count() {
+        assert_eq!(Distribution::new(vec![3.0, 1.0, 2.0]).median(), 2.0);
+        assert_eq!(Distribution::new(vec![1.0, 2.0, 3.0, 4.0]).median(), 2.0);
+    }
+
+    #[test]
+    fn test_mean_and_std_dev() {
+        let dist = Distribution::new(vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert!((dist.mean() - 5.0).abs() < f64::EPSILON);
+        assert!((dist.std_dev() - 2.138_089_935_3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_std_dev_zero_below_two_samples() {
+        assert_eq!(Distribution::new(vec![]).std_dev(), 0.0);
+        assert_eq!(Distribution::new(vec![42.0]).std_dev(), 0.0);
+    }
+
+    #[test]
+    fn test_winsorized_mean_clamps_outliers() {
+        let dist = Distribution::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 100_000.0]);
+        let winsorized = dist.winsorized_mean(20.0);
+        let plain = dist.mean();
+
+        assert!(winsorized < plain, "winsorizing should reduce the outlier's pull");
+    }
+
+    #[test]
+    fn test_winsorized_mean_zero_cut_falls_back_to_mean() {
+        let dist = Distribution::new(vec![1.0, 2.0, 3.0]);
+        assert_eq!(dist.winsorized_mean(0.0), dist.mean());
+    }
+
+    #[test]
+    fn test_session_stats_accumulates_chunks_and_totals() {
+        let mut stats = SessionStats::new();
+        stats.record_chunk(100, 25);
+        stats.record_chunk(300, 75);
+
+        let summary = stats.summary();
+        assert_eq!(summary.total_bytes, 400);
+        assert_eq!(summary.chunk_count, 2);
+        assert_eq!(summary.chunk_size.median, 100.0);
+        assert_eq!(summary.token_estimate.median, 25.0);
+    }
+
+    #[test]
+    fn test_session_stats_tracks_errors_and_suggestions() {
+        let mut stats = SessionStats::new();
+        stats.record_build_errors("tsc", 3);
+        stats.record_build_errors("tsc", 2);
+        stats.record_suggestion("build_errors");
+        stats.record_suggestion("build_errors");
+        stats.record_suggestion("file_read");
+
+        let summary = stats.summary();
+        assert_eq!(summary.tool_error_counts.get("tsc"), Some(&5));
+        assert_eq!(summary.suggestion_counts.get("build_errors"), Some(&2));
+        assert_eq!(summary.suggestion_counts.get("file_read"), Some(&1));
+    }
+
+    #[test]
+    fn test_session_stats_reset_clears_everything() {
+        let mut stats = SessionStats::new();
+        stats.record_chunk(100, 25);
+        stats.record_build_errors("tsc", 1);
+        stats.reset();
+
+        let summary = stats.summary();
+        assert_eq!(summary.total_bytes, 0);
+        assert_eq!(summary.chunk_count, 0);
+        assert!(summary.tool_error_counts.is_empty());
+    }
+}
@@ -13,7 +13,7 @@ src/index.ts:15:10 - error TS2339: Property 'bar' does not exist on type 'string
 src/index.ts:20:1 - error TS2322: Type 'number' is not assignable to type 'string'.
     "#;
 
-    let result = analyzer.analyze(typescript_output);
+    let result = analyzer.analyze(typescript_output.as_bytes());
 
     let has_build_error = result.content_types.iter().any(|ct| {
         matches!(ct, ContentType::BuildError { tool: BuildTool::TypeScript, .. })
@@ -42,7 +42,7 @@ error[E0308]: mismatched types
 error: aborting due to 2 previous errors
     "#;
 
-    let result = analyzer.analyze(rust_output);
+    let result = analyzer.analyze(rust_output.as_bytes());
 
     let has_build_error = result.content_types.iter().any(|ct| {
         matches!(ct, ContentType::BuildError { tool: BuildTool::Rust, .. })
@@ -64,7 +64,7 @@ fn test_detect_eslint_errors() {
 3 problems (2 errors, 1 warning)
     "#;
 
-    let result = analyzer.analyze(eslint_output);
+    let result = analyzer.analyze(eslint_output.as_bytes());
 
     let has_build_error = result.content_types.iter().any(|ct| {
         matches!(ct, ContentType::BuildError { tool: BuildTool::ESLint, .. })
@@ -79,7 +79,7 @@ fn test_detect_large_output() {
 
     // Generer un output volumineux (> 5KB)
     let large = "x".repeat(10000);
-    let result = analyzer.analyze(&large);
+    let result = analyzer.analyze(large.as_bytes());
 
     let has_large_output = result.content_types.iter().any(|ct| {
         matches!(ct, ContentType::LargeOutput { .. })
@@ -93,7 +93,7 @@ fn test_detect_file_read() {
     let mut analyzer = StreamAnalyzer::new();
 
     let file_read = "Reading file: src/main.ts\n```typescript\nconst x = 1;\n```";
-    let result = analyzer.analyze(file_read);
+    let result = analyzer.analyze(file_read.as_bytes());
 
     let has_file_read = result.content_types.iter().any(|ct| {
         matches!(ct, ContentType::FileRead { .. })
@@ -104,23 +104,23 @@ fn test_detect_file_read() {
 
 #[test]
 fn test_strip_ansi_codes() {
-    let analyzer = StreamAnalyzer::new();
+    let mut analyzer = StreamAnalyzer::new();
 
     let with_ansi = "\x1b[31mError:\x1b[0m Something failed";
-    let clean = analyzer.strip_ansi(with_ansi);
+    let result = analyzer.analyze(with_ansi.as_bytes());
 
-    assert_eq!(clean, "Error: Something failed");
-    assert!(!clean.contains("\x1b"), "Should not contain ANSI codes");
+    assert!(result.clean_text.contains("Error: Something failed"));
+    assert!(!result.clean_text.contains('\x1b'), "Should not contain ANSI codes");
 }
 
 #[test]
 fn test_strip_ansi_complex() {
-    let analyzer = StreamAnalyzer::new();
+    let mut analyzer = StreamAnalyzer::new();
 
     let complex = "\x1b[1;31;40mBold Red on Black\x1b[0m \x1b[4mUnderline\x1b[24m";
-    let clean = analyzer.strip_ansi(complex);
+    let result = analyzer.analyze(complex.as_bytes());
 
-    assert_eq!(clean, "Bold Red on Black Underline");
+    assert!(result.clean_text.contains("Bold Red on Black Underline"));
 }
 
 #[test]
@@ -128,7 +128,7 @@ fn test_token_estimation() {
     let mut analyzer = StreamAnalyzer::new();
 
     let text = "Hello, this is a test message with some content.";
-    let result = analyzer.analyze(text);
+    let result = analyzer.analyze(text.as_bytes());
 
     // ~4 chars per token, text is ~50 chars
     assert!(result.token_estimate > 5 && result.token_estimate < 30,
@@ -138,7 +138,7 @@ fn test_token_estimation() {
 #[test]
 fn test_empty_input() {
     let mut analyzer = StreamAnalyzer::new();
-    let result = analyzer.analyze("");
+    let result = analyzer.analyze(b"");
 
     assert!(result.content_types.is_empty() || result.token_estimate == 0);
 }
@@ -148,10 +148,19 @@ fn test_buffer_accumulation() {
     let mut analyzer = StreamAnalyzer::new();
 
     // Analyser plusieurs chunks
-    analyzer.analyze("chunk1 ");
-    analyzer.analyze("chunk2 ");
-    let result = analyzer.analyze("chunk3");
+    analyzer.analyze(b"chunk1 ");
+    analyzer.analyze(b"chunk2 ");
+    let result = analyzer.analyze(b"chunk3");
 
     // Le buffer devrait accumuler
     assert!(result.total_size > 0);
 }
+
+#[test]
+fn test_recent_lines_returns_last_complete_lines() {
+    let mut analyzer = StreamAnalyzer::new();
+    analyzer.analyze(b"line one\nline two\nline three");
+
+    assert_eq!(analyzer.recent_lines(1), "line three");
+    assert_eq!(analyzer.recent_lines(2), "line two\nline three");
+}